@@ -0,0 +1,54 @@
+// Append-only NDJSON log of every capture. Unlike history it's never trimmed
+// or edited, so it can serve as a compliance record.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+pub const FILE_NAME: &str = "audit.log";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub content_hash: String,
+    pub length: usize,
+    pub content_type: String,
+    // Only recorded when the user opts in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+// Append one entry as a single JSON line
+pub fn append(path: &Path, entry: &AuditEntry) -> Result<(), String> {
+    let line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+// Read the last `limit` entries, oldest first. Unreadable lines are skipped.
+pub fn read_tail(path: &Path, limit: usize) -> Result<Vec<AuditEntry>, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to open audit log: {}", e)),
+    };
+
+    let mut entries = std::collections::VecDeque::with_capacity(limit);
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("Failed to read audit log: {}", e))?;
+        if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) {
+            if entries.len() == limit {
+                entries.pop_front();
+            }
+            if limit > 0 {
+                entries.push_back(entry);
+            }
+        }
+    }
+    Ok(entries.into())
+}
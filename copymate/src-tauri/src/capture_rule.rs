@@ -0,0 +1,87 @@
+// Expression-based capture gate. The user writes a boolean evalexpr
+// expression over the candidate item and only content it accepts is stored:
+//
+//   length > 10 && type == "text" && source != "Terminal"
+//   matches("^https?://")
+//
+// Variables: len and length (bytes), lines, type, source ("" when unknown),
+// content. Function: matches(pattern), whether the content matches a regex.
+
+use evalexpr::{
+    build_operator_tree, ContextWithMutableFunctions, ContextWithMutableVariables, EvalexprError, Function,
+    HashMapContext, Node, Value,
+};
+use regex::Regex;
+
+const VARIABLES: [&str; 6] = ["len", "length", "lines", "type", "source", "content"];
+
+// Candidate the expression is tried against when it's set
+const SAMPLE_CONTENT: &str = "https://example.com/sample";
+const SAMPLE_SOURCE: &str = "sample";
+
+// Parse an expression and check it only uses known variables and produces a
+// boolean, so bad rules are rejected when they're set rather than failing
+// every capture afterwards
+pub fn compile(expression: &str) -> Result<Node, String> {
+    let tree = build_operator_tree(expression)
+        .map_err(|e| format!("Invalid capture expression '{}': {}", expression, e))?;
+    if let Some(unknown) = tree.iter_variable_identifiers().find(|name| !VARIABLES.contains(name)) {
+        return Err(format!(
+            "Unknown variable '{}' in capture expression (available: {})",
+            unknown,
+            VARIABLES.join(", ")
+        ));
+    }
+    // A trial run catches unknown functions, bad patterns and non-boolean results
+    let sample = context(SAMPLE_CONTENT, "text", Some(SAMPLE_SOURCE))?;
+    tree.eval_boolean_with_context(&sample)
+        .map_err(|e| format!("Capture expression '{}' failed on a sample item: {}", expression, e))?;
+    Ok(tree)
+}
+
+pub fn validate(expression: &str) -> Result<(), String> {
+    compile(expression).map(|_| ())
+}
+
+// Whether the compiled expression accepts this content. Expressions that fail
+// to evaluate or don't produce a boolean are reported as errors.
+pub fn evaluate(
+    tree: &Node,
+    content: &str,
+    content_type: &str,
+    source_app: Option<&str>
+) -> Result<bool, String> {
+    let context = context(content, content_type, source_app)?;
+    tree.eval_boolean_with_context(&context)
+        .map_err(|e| format!("Capture expression failed: {}", e))
+}
+
+fn context(content: &str, content_type: &str, source_app: Option<&str>) -> Result<HashMapContext, String> {
+    let mut context = HashMapContext::new();
+    let length = Value::Int(content.len() as i64);
+    let variables = [
+        ("len", length.clone()),
+        ("length", length),
+        ("lines", Value::Int(content.lines().count() as i64)),
+        ("type", Value::String(content_type.to_string())),
+        ("source", Value::String(source_app.unwrap_or("").to_string())),
+        ("content", Value::String(content.to_string())),
+    ];
+    for (name, value) in variables {
+        context
+            .set_value(name.to_string(), value)
+            .map_err(|e| format!("Failed to set capture expression variable {}: {}", name, e))?;
+    }
+
+    let text = content.to_string();
+    let matches = Function::new(move |argument| {
+        let pattern = argument.as_string()?;
+        let regex = Regex::new(&pattern)
+            .map_err(|e| EvalexprError::CustomMessage(format!("Invalid pattern '{}': {}", pattern, e)))?;
+        Ok(Value::Boolean(regex.is_match(&text)))
+    });
+    context
+        .set_function("matches".to_string(), matches)
+        .map_err(|e| format!("Failed to set capture expression function matches: {}", e))?;
+    Ok(context)
+}
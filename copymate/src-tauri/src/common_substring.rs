@@ -0,0 +1,176 @@
+// Longest substring shared by several strings, found with a suffix automaton
+// of the shortest one. Each other string is matched against the automaton in
+// a single pass, so the whole thing is linear in the total input length.
+
+use std::collections::HashMap;
+
+struct State {
+    // Length of the longest substring ending in this state
+    len: usize,
+    // Suffix link, None for the root
+    link: Option<usize>,
+    next: HashMap<char, usize>,
+    // Char index in the base string where the substrings of this state end
+    end: usize,
+}
+
+struct SuffixAutomaton {
+    states: Vec<State>,
+}
+
+impl SuffixAutomaton {
+    fn new(chars: &[char]) -> Self {
+        let mut automaton = SuffixAutomaton {
+            states: vec![State { len: 0, link: None, next: HashMap::new(), end: 0 }],
+        };
+        let mut last = 0;
+        for (i, &c) in chars.iter().enumerate() {
+            last = automaton.extend(last, c, i);
+        }
+        automaton
+    }
+
+    fn extend(&mut self, last: usize, c: char, position: usize) -> usize {
+        let current = self.states.len();
+        self.states.push(State {
+            len: self.states[last].len + 1,
+            link: None,
+            next: HashMap::new(),
+            end: position,
+        });
+
+        let mut p = Some(last);
+        while let Some(state) = p {
+            if self.states[state].next.contains_key(&c) {
+                break;
+            }
+            self.states[state].next.insert(c, current);
+            p = self.states[state].link;
+        }
+
+        let Some(p) = p else {
+            self.states[current].link = Some(0);
+            return current;
+        };
+        let q = self.states[p].next[&c];
+        if self.states[p].len + 1 == self.states[q].len {
+            self.states[current].link = Some(q);
+            return current;
+        }
+
+        // Split q so the transition from p stays solid
+        let clone = self.states.len();
+        self.states.push(State {
+            len: self.states[p].len + 1,
+            link: self.states[q].link,
+            next: self.states[q].next.clone(),
+            end: self.states[q].end,
+        });
+        let mut p = Some(p);
+        while let Some(state) = p {
+            if self.states[state].next.get(&c) != Some(&q) {
+                break;
+            }
+            self.states[state].next.insert(c, clone);
+            p = self.states[state].link;
+        }
+        self.states[q].link = Some(clone);
+        self.states[current].link = Some(clone);
+        current
+    }
+
+    // Longest match of `text` that ends in each state
+    fn match_lengths(&self, text: &[char], by_len_desc: &[usize]) -> Vec<usize> {
+        let mut best = vec![0; self.states.len()];
+        let (mut state, mut matched) = (0, 0);
+        for c in text {
+            while state != 0 && !self.states[state].next.contains_key(c) {
+                state = self.states[state].link.unwrap_or(0);
+                matched = self.states[state].len;
+            }
+            match self.states[state].next.get(c) {
+                Some(&next) => {
+                    state = next;
+                    matched += 1;
+                }
+                None => {
+                    state = 0;
+                    matched = 0;
+                }
+            }
+            best[state] = best[state].max(matched);
+        }
+        // A match ending in a state also matches its suffix-link ancestors
+        for &state in by_len_desc {
+            if let Some(link) = self.states[state].link {
+                best[link] = best[link].max(best[state].min(self.states[link].len));
+            }
+        }
+        best
+    }
+}
+
+// Longest substring (by chars) present in every string. Empty when there's
+// nothing in common or no strings at all.
+pub fn longest(texts: &[&str]) -> String {
+    let Some(base) = texts.iter().min_by_key(|text| text.chars().count()) else {
+        return String::new();
+    };
+    let base: Vec<char> = base.chars().collect();
+    let automaton = SuffixAutomaton::new(&base);
+
+    let mut by_len_desc: Vec<usize> = (0..automaton.states.len()).collect();
+    by_len_desc.sort_unstable_by(|a, b| automaton.states[*b].len.cmp(&automaton.states[*a].len));
+
+    // Longest substring of the base common to every text, per state
+    let mut common: Vec<usize> = automaton.states.iter().map(|state| state.len).collect();
+    for text in texts {
+        let chars: Vec<char> = text.chars().collect();
+        let matched = automaton.match_lengths(&chars, &by_len_desc);
+        for (common, matched) in common.iter_mut().zip(matched) {
+            *common = (*common).min(matched);
+        }
+    }
+
+    let Some((state, &len)) = common.iter().enumerate().max_by_key(|(_, len)| **len) else {
+        return String::new();
+    };
+    if len == 0 {
+        return String::new();
+    }
+    let end = automaton.states[state].end;
+    base[end + 1 - len..=end].iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::longest;
+
+    #[test]
+    fn no_strings() {
+        assert_eq!(longest(&[]), "");
+    }
+
+    #[test]
+    fn one_string_is_its_own_longest() {
+        assert_eq!(longest(&["hello"]), "hello");
+    }
+
+    #[test]
+    fn nothing_in_common() {
+        assert_eq!(longest(&["abc", "xyz"]), "");
+        assert_eq!(longest(&["abc", ""]), "");
+    }
+
+    #[test]
+    fn shared_run_anywhere_in_each_string() {
+        assert_eq!(longest(&["xabcdy", "zzabcd", "abcdq"]), "abcd");
+        assert_eq!(longest(&["banana", "ananas"]), "anana");
+    }
+
+    #[test]
+    fn multibyte_input() {
+        assert_eq!(longest(&["héllo wörld", "say héllo"]), "héllo");
+        assert_eq!(longest(&["🙂🙃ab", "x🙃aby"]), "🙃ab");
+    }
+}
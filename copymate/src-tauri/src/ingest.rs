@@ -0,0 +1,224 @@
+// Local HTTP endpoint that lets scripts push items into history. It only
+// listens on localhost, requires a bearer token, and caps request size.
+//
+//   POST /items
+//   Authorization: Bearer <token>
+//   {"content": "..."}
+
+use serde::Deserialize;
+use serde_json::json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+pub const DEFAULT_PORT: u16 = 41537;
+
+// Largest request body accepted
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+// Largest request line or header line accepted
+const MAX_LINE_BYTES: usize = 8 * 1024;
+// Most header lines accepted
+const MAX_HEADERS: usize = 64;
+// Longest a single read or write may block
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+// Longest a whole request may take. Connections are served on the accept
+// thread, so this bounds how long a slow client can hold the server (and
+// how long stopping it can take).
+const CONNECTION_DEADLINE: Duration = Duration::from_secs(15);
+// How often the accept loop checks whether it should stop
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// Source app recorded for pushed items
+const SOURCE_NAME: &str = "ingest";
+
+#[derive(Deserialize)]
+struct IngestRequest {
+    content: String,
+}
+
+// Socket reader that gives up once the connection's deadline has passed,
+// shrinking the read timeout so no single read can run past it
+struct DeadlineStream {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "request took too long"));
+        }
+        self.stream.set_read_timeout(Some(remaining.min(READ_TIMEOUT)))?;
+        self.stream.read(buf)
+    }
+}
+
+// A running server. Stopping it waits for the accept loop to exit, so the
+// port is free again once `stop` returns.
+pub struct IngestHandle {
+    pub port: u16,
+    pub token: String,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl IngestHandle {
+    pub fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if self.thread.join().is_err() {
+            eprintln!("Ingest server thread panicked");
+        }
+    }
+}
+
+// Bind the listener and serve requests on a background thread. Returns a
+// handle that stops the server, or an error if the port can't be bound.
+pub fn start(app: AppHandle, port: u16, token: String) -> Result<IngestHandle, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to bind ingest server to port {}: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure ingest server: {}", e))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = stop.clone();
+    let thread_token = token.clone();
+    let thread = thread::spawn(move || {
+        while !stop_clone.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Err(e) = handle_connection(&app, stream, &thread_token) {
+                        eprintln!("Ingest request failed: {}", e);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(e) => eprintln!("Ingest server accept failed: {}", e),
+            }
+        }
+    });
+    Ok(IngestHandle { port, token, stop, thread })
+}
+
+fn handle_connection(app: &AppHandle, stream: TcpStream, token: &str) -> Result<(), String> {
+    // Accepted sockets can inherit non-blocking mode from the listener
+    stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(READ_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(DeadlineStream {
+        stream,
+        deadline: Instant::now() + CONNECTION_DEADLINE,
+    });
+
+    let (status, body) = match process_request(app, &mut reader, token) {
+        Ok(response) => response,
+        Err((status, message)) => (status, json!({ "error": message }).to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    writer.write_all(response.as_bytes()).map_err(|e| e.to_string())
+}
+
+// Returns the status line and JSON body to respond with
+fn process_request(
+    app: &AppHandle,
+    reader: &mut BufReader<DeadlineStream>,
+    token: &str
+) -> Result<(&'static str, String), (&'static str, String)> {
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    if path != "/items" {
+        return Err(("404 Not Found", "Unknown path".to_string()));
+    }
+    if method != "POST" {
+        return Err(("405 Method Not Allowed", "Only POST is supported".to_string()));
+    }
+
+    let mut content_length = None;
+    let mut authorized = false;
+    let mut header_count = 0;
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        header_count += 1;
+        if header_count > MAX_HEADERS {
+            return Err(("431 Request Header Fields Too Large", format!("More than {} headers", MAX_HEADERS)));
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(("400 Bad Request", "Malformed header".to_string()));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse::<usize>().ok();
+        } else if name.eq_ignore_ascii_case("authorization") {
+            authorized = value
+                .strip_prefix("Bearer ")
+                .is_some_and(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()));
+        }
+    }
+
+    if !authorized {
+        return Err(("401 Unauthorized", "Missing or invalid token".to_string()));
+    }
+    let content_length = content_length.ok_or(("411 Length Required", "Content-Length is required".to_string()))?;
+    if content_length > MAX_BODY_BYTES {
+        return Err(("413 Payload Too Large", format!("Body exceeds {} bytes", MAX_BODY_BYTES)));
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| ("400 Bad Request", format!("Failed to read body: {}", e)))?;
+    let request: IngestRequest = serde_json::from_slice(&body)
+        .map_err(|e| ("400 Bad Request", format!("Invalid JSON body: {}", e)))?;
+
+    match crate::add_item_to_history(app, &request.content, Some(SOURCE_NAME.to_string()), None) {
+        Ok(Some(item)) => {
+            let id = item.id;
+            crate::emit_clipboard_updated(app, item);
+            Ok(("201 Created", json!({ "id": id }).to_string()))
+        }
+        Ok(None) => Ok(("200 OK", json!({ "skipped": true }).to_string())),
+        Err(e) => Err(("500 Internal Server Error", e)),
+    }
+}
+
+// Read one CRLF-terminated line, refusing overly long ones
+fn read_line(reader: &mut BufReader<DeadlineStream>) -> Result<String, (&'static str, String)> {
+    let mut line = String::new();
+    let read = reader
+        .by_ref()
+        .take(MAX_LINE_BYTES as u64)
+        .read_line(&mut line)
+        .map_err(|e| ("400 Bad Request", format!("Failed to read request: {}", e)))?;
+    if read == 0 || !line.ends_with('\n') {
+        return Err(("400 Bad Request", "Truncated or oversized request line".to_string()));
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// Compare tokens without bailing out at the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// Random hex token for new installs
+pub fn generate_token() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
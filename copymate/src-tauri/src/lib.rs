@@ -1,11 +1,33 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-use std::collections::VecDeque;
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH, Duration};
+use std::time::{SystemTime, UNIX_EPOCH, Duration, Instant};
 use std::thread;
-use tauri::{State, AppHandle, Emitter};
+use tauri::{State, AppHandle, Emitter, Manager};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use serde::{Deserialize, Serialize};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use hmac::{Hmac, Mac};
+
+mod audit;
+mod capture_rule;
+mod common_substring;
+mod ingest;
+mod pii;
+mod platform;
+mod redact;
+mod search_index;
+mod secrets;
+mod storage;
+mod transform;
+mod watcher;
+mod webhook;
 
 // Data structure for clipboard items
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,193 +36,3667 @@ pub struct ClipboardItem {
     pub content: String,
     pub timestamp: u64,
     pub content_type: String,
+    // Application that had focus when the item was captured, if known
+    #[serde(default)]
+    pub source_app: Option<String>,
+    // Hex SHA-256 of the original content
+    #[serde(default)]
+    pub content_hash: String,
+    // Length in bytes of the original content
+    #[serde(default)]
+    pub content_length: usize,
+    // Set when the content matched a privacy pattern and only its hash was kept
+    #[serde(default)]
+    pub hash_only: bool,
+    // User- and rule-assigned tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Pinned items are listed first and survive trimming
+    #[serde(default)]
+    pub pinned: bool,
+    // Free-form key/value data attached by integrations. Not searched, but
+    // saved and exported with the item.
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    // Index of the display the user was working on at capture time, if known
+    #[serde(default)]
+    pub display: Option<u32>,
+    // When the item was last brought back into use, e.g. by promote_current
+    #[serde(default)]
+    pub last_used: Option<u64>,
+    // How many times this content has been captured in a row
+    #[serde(default = "default_occurrence_count")]
+    pub occurrence_count: u64,
+    // Content-addressed id: the same for identical content no matter what
+    // numeric id the item gets, so external references survive export/import
+    #[serde(default)]
+    pub cid: String,
+    // Flagged by the user or a secret scan as likely sensitive
+    #[serde(default)]
+    pub secret: bool,
+    // List-view preview, sized by the preview_lengths setting for the type
+    #[serde(default)]
+    pub preview: String,
+}
+
+fn default_occurrence_count() -> u64 {
+    1
+}
+
+// Content id for content with the given hash
+fn content_id(content_hash: &str) -> String {
+    format!("sha256:{}", content_hash)
+}
+
+// Content id for hash-only content with the given keyed hash
+fn keyed_content_id(content_hash: &str) -> String {
+    format!("hmac-sha256:{}", content_hash)
+}
+
+// Give items loaded from older files the cid they would have been created with
+fn backfill_cids<'a>(items: impl IntoIterator<Item = &'a mut ClipboardItem>) {
+    for item in items {
+        if item.cid.is_empty() {
+            if item.content_hash.is_empty() {
+                item.content_hash = hash_content(&item.content);
+            }
+            item.cid = content_id(&item.content_hash);
+        }
+    }
+}
+
+// Application state to store clipboard history
+pub type ClipboardHistory = Arc<Mutex<VecDeque<ClipboardItem>>>;
+
+// Most items kept in history; the oldest unpinned ones are dropped beyond this
+const MAX_HISTORY_ITEMS: usize = 100;
+
+// Content CopyMate has put on the clipboard itself, keyed by hash with the
+// time of the write, so the monitor doesn't capture it back
+#[derive(Debug, Default)]
+pub struct SelfWriteTracker {
+    pending: HashMap<String, Instant>,
+}
+
+impl SelfWriteTracker {
+    pub fn record(&mut self, content: &str) {
+        self.pending.insert(hash_content(content), Instant::now());
+    }
+
+    // Consume the entry for this content, returning whether there was one
+    pub fn take(&mut self, content: &str) -> bool {
+        self.pending.remove(&hash_content(content)).is_some()
+    }
+
+    // Drop entries for writes that never came back round, e.g. because another
+    // app replaced the clipboard first
+    pub fn sweep(&mut self, max_age: Duration) {
+        self.pending.retain(|_, written| written.elapsed() < max_age);
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+pub type SelfWrites = Arc<Mutex<SelfWriteTracker>>;
+
+// Self-write entries older than this are swept if no setting overrides it
+const DEFAULT_SELF_WRITE_EXPIRY_SECS: u64 = 10;
+
+// Named, separate histories. The active workspace's items live in
+// ClipboardHistory; the others are parked here until switched to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceStore {
+    pub active: String,
+    pub inactive: HashMap<String, VecDeque<ClipboardItem>>,
+}
+
+impl Default for WorkspaceStore {
+    fn default() -> Self {
+        WorkspaceStore {
+            active: DEFAULT_WORKSPACE.to_string(),
+            inactive: HashMap::new(),
+        }
+    }
+}
+
+pub type Workspaces = Arc<Mutex<WorkspaceStore>>;
+
+const DEFAULT_WORKSPACE: &str = "default";
+
+// Item ids assigned to quick slots 1-9 (index 0 is slot 1)
+pub type QuickSlots = Arc<Mutex<[Option<u64>; 9]>>;
+
+// A named search query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+}
+
+pub type SavedSearches = Arc<Mutex<Vec<SavedSearch>>>;
+
+// User-defined order of pinned item ids
+pub type PinOrder = Arc<Mutex<Vec<u64>>>;
+
+// Trigram index over history content, kept in sync with every mutation
+pub type HistoryIndex = Arc<Mutex<search_index::SearchIndex>>;
+
+// Rule that tags captured content matching a regex
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoTagRule {
+    pub pattern: String,
+    pub tag: String,
+}
+
+// Auto-tag rules along with their compiled patterns
+pub type AutoTagRules = Arc<Mutex<Vec<(AutoTagRule, Regex)>>>;
+
+// Redaction rulesets by name, with their patterns compiled
+type CompiledRulesets = HashMap<String, (Vec<String>, Vec<Regex>)>;
+
+// User-defined redaction rulesets
+pub type RedactionRulesets = Arc<Mutex<CompiledRulesets>>;
+
+// Helper to compile auto-tag rules, failing on the first invalid pattern
+fn compile_auto_tag_rules(rules: Vec<AutoTagRule>) -> Result<Vec<(AutoTagRule, Regex)>, String> {
+    rules
+        .into_iter()
+        .map(|rule| {
+            let regex = Regex::new(&rule.pattern).map_err(|e| format!("Invalid pattern '{}': {}", rule.pattern, e))?;
+            Ok((rule, regex))
+        })
+        .collect()
+}
+
+// Helper to compile named redaction rulesets, refusing built-in names
+fn compile_redaction_rulesets(
+    rulesets: HashMap<String, Vec<String>>
+) -> Result<CompiledRulesets, String> {
+    rulesets
+        .into_iter()
+        .map(|(name, patterns)| {
+            if redact::builtin_patterns(&name).is_some() {
+                return Err(format!("'{}' is a built-in ruleset", name));
+            }
+            let regexes = compile_patterns(&patterns)?;
+            Ok((name, (patterns, regexes)))
+        })
+        .collect()
+}
+
+// Where captures go while append mode is on
+#[derive(Debug, Clone)]
+pub struct AppendModeState {
+    // Item new captures are appended to; None until the first capture creates it
+    pub target_id: Option<u64>,
+    pub separator: String,
+}
+
+pub type AppendMode = Arc<Mutex<Option<AppendModeState>>>;
+
+// Payload of the `clipboard-updated` event. Oversized content is swapped for a
+// preview so the frontend can fetch the full item with `get_history_item`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardUpdatedPayload {
+    #[serde(flatten)]
+    pub item: ClipboardItem,
+    pub content_omitted: bool,
+}
+
+impl ClipboardUpdatedPayload {
+    fn new(mut item: ClipboardItem, max_bytes: Option<usize>) -> Self {
+        let content_omitted = max_bytes.is_some_and(|max| item.content.len() > max);
+        if content_omitted {
+            item.content = item.preview.clone();
+        }
+        ClipboardUpdatedPayload { item, content_omitted }
+    }
+}
+
+// Number of characters kept in a preview
+const PREVIEW_CHARS: usize = 100;
+
+// Ids present in history when the last checkpoint was taken
+pub type HistoryCheckpoint = Arc<Mutex<Option<HashSet<u64>>>>;
+
+// Items added and ids removed since the last checkpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryDelta {
+    pub added: Vec<ClipboardItem>,
+    pub removed: Vec<u64>,
+}
+
+// User-configurable settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Skip captures while a screen share / recording looks active
+    pub pause_during_screenshare: bool,
+    // Emit a `pii-detected` event when a card number, SSN or email is captured
+    pub warn_on_pii: bool,
+    // Don't store items containing a Luhn-valid card number
+    pub skip_card_numbers: bool,
+    // Regexes whose matches are stored only as a hash and length
+    pub hash_only_patterns: Vec<String>,
+    // When non-empty, only content matching one of these regexes is captured
+    pub allowlist_patterns: Vec<String>,
+    // Content matching any of these regexes is never captured
+    pub blocklist_patterns: Vec<String>,
+    // Include the captured content itself in audit log entries
+    pub audit_include_content: bool,
+    // Content larger than this is left out of `clipboard-updated` events
+    pub max_emit_payload_bytes: Option<usize>,
+    // Ignore leading/trailing whitespace and whitespace runs when deduplicating
+    pub dedup_ignore_whitespace: bool,
+    // Ignore case when deduplicating (stored content keeps its case)
+    pub dedup_case_insensitive: bool,
+    // URL each captured item is POSTed to
+    pub capture_webhook_url: Option<String>,
+    // Include the captured content itself in webhook payloads
+    pub webhook_include_content: bool,
+    // Accept items POSTed to a localhost HTTP endpoint
+    pub enable_ingest_server: bool,
+    // Port for the ingest server (defaults to ingest::DEFAULT_PORT)
+    pub ingest_port: Option<u16>,
+    // Bearer token ingest requests must present; generated when first needed
+    pub ingest_token: Option<String>,
+    // Pause monitoring after this long without keyboard/mouse input, resuming
+    // on activity. Ignored where idle time can't be detected.
+    pub auto_pause_when_idle_secs: Option<u64>,
+    // Minimum time between history writes (defaults to DEFAULT_PERSIST_DEBOUNCE_MS)
+    pub persist_debounce_ms: Option<u64>,
+    // How long a clipboard write by CopyMate is remembered so the monitor can
+    // skip it (defaults to DEFAULT_SELF_WRITE_EXPIRY_SECS)
+    pub self_write_expiry_secs: Option<u64>,
+    // A repeat of the latest item within this many seconds of when it was last
+    // seen is ignored outright instead of bumping its count and last_used
+    pub min_repeat_interval_secs: Option<u64>,
+    // Boolean expression content must satisfy to be captured (see capture_rule)
+    pub capture_expression: Option<String>,
+    // Preview length in characters per content type. Line breaks are kept, so
+    // a longer limit shows several lines. Types without an entry get the
+    // first line, up to PREVIEW_CHARS.
+    pub preview_lengths: HashMap<String, usize>,
+}
+
+pub type AppSettings = Arc<Mutex<Settings>>;
+
+// The settings' capture patterns, compiled whenever settings change rather
+// than on every capture
+#[derive(Debug, Default)]
+pub struct CompiledFilters {
+    allowlist: Vec<Regex>,
+    blocklist: Vec<Regex>,
+    hash_only: Vec<Regex>,
+    capture_expression: Option<evalexpr::Node>,
+}
+
+impl CompiledFilters {
+    fn compile(settings: &Settings) -> Result<Self, String> {
+        Ok(CompiledFilters {
+            allowlist: compile_patterns(&settings.allowlist_patterns)?,
+            blocklist: compile_patterns(&settings.blocklist_patterns)?,
+            hash_only: compile_patterns(&settings.hash_only_patterns)?,
+            capture_expression: settings.capture_expression.as_deref().map(capture_rule::compile).transpose()?,
+        })
+    }
+}
+
+pub type CaptureFilters = Arc<Mutex<CompiledFilters>>;
+
+// Per-install key for hashing hash-only content, so its hashes can't be
+// checked against guesses elsewhere. Loaded from (or saved to) the data
+// directory; until then, or without one, a random key lives in memory.
+pub type HashKey = Arc<Mutex<Vec<u8>>>;
+
+// Flag set while the user has paused (incognito) capturing
+pub type CapturePaused = Arc<Mutex<bool>>;
+
+// Cancel flag of the pending auto-resume from pause_for, if any
+pub type PauseTimer = Arc<Mutex<Option<Arc<AtomicBool>>>>;
+
+// Rolling window of recent clipboard read durations
+#[derive(Debug, Default)]
+pub struct LatencyStats {
+    samples_ms: VecDeque<f64>,
+}
+
+impl LatencyStats {
+    // Number of recent reads the average is computed over
+    const WINDOW: usize = 50;
+
+    fn record(&mut self, elapsed: Duration) {
+        self.samples_ms.push_back(elapsed.as_secs_f64() * 1000.0);
+        if self.samples_ms.len() > Self::WINDOW {
+            self.samples_ms.pop_front();
+        }
+    }
+
+    fn average_ms(&self) -> f64 {
+        if self.samples_ms.is_empty() {
+            return 0.0;
+        }
+        self.samples_ms.iter().sum::<f64>() / self.samples_ms.len() as f64
+    }
+}
+
+pub type ReadLatency = Arc<Mutex<LatencyStats>>;
+
+// Content that appears more than once in history
+#[derive(Debug, Clone, Serialize)]
+pub struct RepeatedContent {
+    pub preview: String,
+    pub count: u64,
+}
+
+// How varied the history is
+#[derive(Debug, Clone, Serialize)]
+pub struct DiversityStats {
+    pub total_items: usize,
+    pub unique_items: usize,
+    // unique_items / total_items (1.0 for an empty history)
+    pub unique_ratio: f64,
+    // Mean trigram Jaccard similarity between neighbouring items, 0.0 to 1.0
+    pub avg_consecutive_similarity: f64,
+    pub most_repeated: Option<RepeatedContent>,
+}
+
+// Only this many characters of each item are compared for similarity
+const SIMILARITY_MAX_CHARS: usize = 10_000;
+
+// Common English words left out of word frequency reports
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been", "but", "by",
+    "can", "could", "did", "do", "does", "for", "from", "had", "has", "have", "he", "her", "his", "how", "i",
+    "if", "in", "into", "is", "it", "its", "just", "me", "my", "no", "not", "of", "on", "one", "or", "our",
+    "out", "she", "so", "some", "than", "that", "the", "their", "them", "then", "there", "these", "they",
+    "this", "to", "up", "us", "was", "we", "were", "what", "when", "which", "who", "will", "with", "would",
+    "you", "your",
+];
+
+// Only this many characters of each item are searched for a common substring
+const COMMON_SUBSTRING_MAX_CHARS: usize = 10_000;
+
+// Items captured on one UTC calendar day
+#[derive(Debug, Clone, Serialize)]
+pub struct DayGroup {
+    // YYYY-MM-DD
+    pub day: String,
+    pub items: Vec<ClipboardItem>,
+}
+
+// Items of one content type, grouped by day (newest day first)
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeGroup {
+    pub content_type: String,
+    pub days: Vec<DayGroup>,
+}
+
+// A run of items with no long gap between consecutive captures
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRange {
+    pub start: u64,
+    pub end: u64,
+    pub item_count: usize,
+}
+
+// Counters for each outcome of the capture pipeline, for this session
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CaptureMetrics {
+    pub total_captured: u64,
+    pub total_skipped_duplicate: u64,
+    pub total_skipped_empty: u64,
+    pub total_skipped_filtered: u64,
+    pub total_skipped_paused: u64,
+    pub total_skipped_self_write: u64,
+}
+
+pub type Metrics = Arc<Mutex<CaptureMetrics>>;
+
+// Health of on-disk persistence
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PersistenceStatus {
+    pub writable: bool,
+    pub path: String,
+    pub last_write_ok: bool,
+    // Why history can't be saved, when it can't
+    pub error: Option<String>,
+}
+
+pub type Persistence = Arc<Mutex<PersistenceStatus>>;
+
+// Debounce state for history writes. Mutations mark history dirty and
+// schedule a single write; further mutations before it runs are batched in.
+#[derive(Debug, Default)]
+pub struct PersistQueue {
+    dirty: bool,
+    write_scheduled: bool,
+    last_write: Option<Instant>,
+    last_write_ts: Option<u64>,
+}
+
+pub type HistoryWriter = Arc<Mutex<PersistQueue>>;
+
+// Diagnostics for the debounced history writer
+#[derive(Debug, Clone, Serialize)]
+pub struct PersistStats {
+    // Whether there are changes not yet written to disk
+    pub pending: bool,
+    // Unix time of the last successful history write
+    pub last_write_ts: Option<u64>,
+}
+
+const DEFAULT_PERSIST_DEBOUNCE_MS: u64 = 1000;
+
+// Size of history in memory compared with its saved file
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageReport {
+    // Approximate: item structs plus the heap data of their strings and maps
+    pub in_memory_bytes: usize,
+    // Size of the history file, 0 if it hasn't been written yet
+    pub on_disk_bytes: u64,
+    pub item_count: usize,
+    // Whether the history file is compressed (it's currently plain JSON)
+    pub compressed: bool,
+}
+
+// The running ingest server, if any
+pub type IngestServer = Arc<Mutex<Option<ingest::IngestHandle>>>;
+
+// Name of the change-detection backend the monitor is using, once started
+pub type MonitorBackend = Arc<Mutex<Option<&'static str>>>;
+
+// Id of the history item CopyMate itself most recently put on the clipboard
+pub type LastCopied = Arc<Mutex<Option<u64>>>;
+
+// Longest the monitor waits for a clipboard change before doing housekeeping
+const MONITOR_TICK: Duration = Duration::from_millis(500);
+
+// How often the monitor re-checks for screen sharing
+const SCREENSHARE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+// How often the monitor re-checks input idle time
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// Tauri command to get current clipboard content
+#[tauri::command]
+fn get_clipboard_text(app: tauri::AppHandle) -> Result<String, String> {
+    match app.clipboard().read_text() {
+        Ok(text) => Ok(text),
+        Err(e) => Err(format!("Failed to read clipboard: {}", e)),
+    }
+}
+
+// Tauri command to get clipboard history
+#[tauri::command]
+async fn get_clipboard_history(
+    history: State<'_, ClipboardHistory>,
+    pin_order: State<'_, PinOrder>
+) -> Result<Vec<ClipboardItem>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let pin_order_guard = pin_order.lock().map_err(|e| format!("Failed to lock pin order: {}", e))?;
+
+    // Pinned items first in their custom order, unlisted pins by recency
+    let mut pinned: Vec<&ClipboardItem> = history_guard.iter().filter(|item| item.pinned).collect();
+    pinned.sort_by_key(|item| {
+        pin_order_guard
+            .iter()
+            .position(|id| *id == item.id)
+            .unwrap_or(usize::MAX)
+    });
+
+    Ok(pinned
+        .into_iter()
+        .chain(history_guard.iter().filter(|item| !item.pinned))
+        .cloned()
+        .collect())
+}
+
+// Tauri command to pin an item
+#[tauri::command]
+async fn pin_item(app: AppHandle, id: u64, history: State<'_, ClipboardHistory>) -> Result<(), String> {
+    set_item_pinned(&history, id, true)?;
+    persist_history(&app);
+    Ok(())
+}
+
+// Tauri command to unpin an item
+#[tauri::command]
+async fn unpin_item(
+    app: AppHandle,
+    id: u64,
+    history: State<'_, ClipboardHistory>,
+    pin_order: State<'_, PinOrder>
+) -> Result<(), String> {
+    set_item_pinned(&history, id, false)?;
+    {
+        let mut pin_order_guard = pin_order.lock().map_err(|e| format!("Failed to lock pin order: {}", e))?;
+        pin_order_guard.retain(|pinned_id| *pinned_id != id);
+    }
+    persist_history(&app);
+    persist_pin_order(&app);
+    Ok(())
+}
+
+// Helper to set an item's pinned flag
+fn set_item_pinned(history: &ClipboardHistory, id: u64, pinned: bool) -> Result<(), String> {
+    let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let item = history_guard
+        .iter_mut()
+        .find(|item| item.id == id)
+        .ok_or_else(|| format!("No history item with id {}", id))?;
+    item.pinned = pinned;
+    Ok(())
+}
+
+// Tauri command to set the display order of pinned items
+#[tauri::command]
+async fn set_pin_order(app: AppHandle, ids: Vec<u64>, pin_order: State<'_, PinOrder>) -> Result<(), String> {
+    {
+        let mut pin_order_guard = pin_order.lock().map_err(|e| format!("Failed to lock pin order: {}", e))?;
+        *pin_order_guard = ids;
+    }
+    persist_pin_order(&app);
+    Ok(())
+}
+
+// Tauri command to get a single history item by id (e.g. when an event omitted its content)
+#[tauri::command]
+async fn get_history_item(id: u64, history: State<'_, ClipboardHistory>) -> Result<ClipboardItem, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    history_guard
+        .iter()
+        .find(|item| item.id == id)
+        .cloned()
+        .ok_or_else(|| format!("No history item with id {}", id))
+}
+
+// Tauri command to get the newest history item with the given content id
+#[tauri::command]
+async fn get_by_cid(cid: String, history: State<'_, ClipboardHistory>) -> Result<Option<ClipboardItem>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    Ok(history_guard.iter().find(|item| item.cid == cid).cloned())
+}
+
+// Tauri command to find items whose content contains the query (case-insensitive).
+// The trigram index narrows the candidates before the exact match.
+#[tauri::command]
+async fn search_history(
+    query: String,
+    history: State<'_, ClipboardHistory>,
+    index: State<'_, HistoryIndex>
+) -> Result<Vec<ClipboardItem>, String> {
+    search_items(&history, &index, &query)
+}
+
+// Helper to run a search query against history
+fn search_items(history: &ClipboardHistory, index: &HistoryIndex, query: &str) -> Result<Vec<ClipboardItem>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let candidates = {
+        let index_guard = index.lock().map_err(|e| format!("Failed to lock search index: {}", e))?;
+        index_guard.candidates(query)
+    };
+
+    let needle = query.to_lowercase();
+    Ok(history_guard
+        .iter()
+        .filter(|item| candidates.as_ref().is_none_or(|ids| ids.contains(&item.id)))
+        .filter(|item| item.content.to_lowercase().contains(&needle))
+        .cloned()
+        .collect())
+}
+
+// Tauri command to save a search query under a name, replacing any existing one
+#[tauri::command]
+async fn save_search(
+    app: AppHandle,
+    name: String,
+    query: String,
+    saved_searches: State<'_, SavedSearches>
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Saved search name must not be empty".to_string());
+    }
+    {
+        let mut searches_guard = saved_searches.lock().map_err(|e| format!("Failed to lock saved searches: {}", e))?;
+        match searches_guard.iter_mut().find(|search| search.name == name) {
+            Some(search) => search.query = query,
+            None => searches_guard.push(SavedSearch { name, query }),
+        }
+    }
+    persist_saved_searches(&app);
+    Ok(())
+}
+
+// Tauri command to list saved searches in the order they were created
+#[tauri::command]
+async fn list_saved_searches(saved_searches: State<'_, SavedSearches>) -> Result<Vec<SavedSearch>, String> {
+    let searches_guard = saved_searches.lock().map_err(|e| format!("Failed to lock saved searches: {}", e))?;
+    Ok(searches_guard.clone())
+}
+
+// Tauri command to run a saved search against history
+#[tauri::command]
+async fn run_saved_search(
+    name: String,
+    saved_searches: State<'_, SavedSearches>,
+    history: State<'_, ClipboardHistory>,
+    index: State<'_, HistoryIndex>
+) -> Result<Vec<ClipboardItem>, String> {
+    let query = {
+        let searches_guard = saved_searches.lock().map_err(|e| format!("Failed to lock saved searches: {}", e))?;
+        searches_guard
+            .iter()
+            .find(|search| search.name == name)
+            .map(|search| search.query.clone())
+            .ok_or_else(|| format!("No saved search named '{}'", name))?
+    };
+    search_items(&history, &index, &query)
+}
+
+// Tauri command to rebuild the search index from scratch
+#[tauri::command]
+async fn rebuild_search_index(
+    history: State<'_, ClipboardHistory>,
+    index: State<'_, HistoryIndex>
+) -> Result<(), String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let mut index_guard = index.lock().map_err(|e| format!("Failed to lock search index: {}", e))?;
+    index_guard.rebuild(history_guard.iter());
+    Ok(())
+}
+
+// Tauri command to report how varied the clipboard history is
+#[tauri::command]
+async fn get_diversity_stats(history: State<'_, ClipboardHistory>) -> Result<DiversityStats, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+
+    // Count occurrences by hash; hash-only items have no content to hash again
+    let mut counts: HashMap<String, (u64, &ClipboardItem)> = HashMap::new();
+    for item in history_guard.iter() {
+        let key = if item.content_hash.is_empty() { hash_content(&item.content) } else { item.content_hash.clone() };
+        counts.entry(key).or_insert((0, item)).0 += 1;
+    }
+
+    let most_repeated = counts
+        .values()
+        .filter(|(count, _)| *count > 1)
+        .max_by_key(|(count, item)| (*count, item.timestamp))
+        .map(|(count, item)| RepeatedContent { preview: make_preview(&item.content), count: *count });
+
+    let grams: Vec<HashSet<String>> = history_guard
+        .iter()
+        .map(|item| {
+            let truncated: String = item.content.chars().take(SIMILARITY_MAX_CHARS).collect();
+            search_index::trigrams(&truncated)
+        })
+        .collect();
+    let similarities: Vec<f64> = grams.windows(2).map(|pair| jaccard(&pair[0], &pair[1])).collect();
+    let avg_consecutive_similarity = if similarities.is_empty() {
+        0.0
+    } else {
+        similarities.iter().sum::<f64>() / similarities.len() as f64
+    };
+
+    let total_items = history_guard.len();
+    let unique_items = counts.len();
+    Ok(DiversityStats {
+        total_items,
+        unique_items,
+        unique_ratio: if total_items == 0 { 1.0 } else { unique_items as f64 / total_items as f64 },
+        avg_consecutive_similarity,
+        most_repeated,
+    })
+}
+
+// Size of the intersection over size of the union (1.0 when both are empty)
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+// Tauri command to count items by the class of their first character:
+// letter, digit, symbol, whitespace, non_ascii, or empty for items with no
+// stored content
+#[tauri::command]
+async fn content_category_histogram(history: State<'_, ClipboardHistory>) -> Result<HashMap<String, u64>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let mut histogram: HashMap<String, u64> = HashMap::new();
+    for item in history_guard.iter() {
+        let category = match item.content.chars().next() {
+            None => "empty",
+            Some(c) if !c.is_ascii() => "non_ascii",
+            Some(c) if c.is_ascii_alphabetic() => "letter",
+            Some(c) if c.is_ascii_digit() => "digit",
+            Some(c) if c.is_ascii_whitespace() => "whitespace",
+            Some(_) => "symbol",
+        };
+        *histogram.entry(category.to_string()).or_insert(0) += 1;
+    }
+    Ok(histogram)
+}
+
+// Tauri command to get the items captured while a given display was active
+#[tauri::command]
+async fn get_history_by_display(index: u32, history: State<'_, ClipboardHistory>) -> Result<Vec<ClipboardItem>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    Ok(history_guard
+        .iter()
+        .filter(|item| item.display == Some(index))
+        .cloned()
+        .collect())
+}
+
+// Tauri command to get history as a tree: content type, then UTC day, then
+// items. Types appear in order of their most recent item, days newest first,
+// and items keep their history order.
+#[tauri::command]
+async fn get_history_tree(history: State<'_, ClipboardHistory>) -> Result<Vec<TypeGroup>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let mut groups: Vec<TypeGroup> = Vec::new();
+    for item in history_guard.iter() {
+        let group_index = match groups.iter().position(|group| group.content_type == item.content_type) {
+            Some(index) => index,
+            None => {
+                groups.push(TypeGroup { content_type: item.content_type.clone(), days: Vec::new() });
+                groups.len() - 1
+            }
+        };
+        let days = &mut groups[group_index].days;
+        let day = utc_day(item.timestamp);
+        match days.iter_mut().find(|group| group.day == day) {
+            Some(group) => group.items.push(item.clone()),
+            None => days.push(DayGroup { day, items: vec![item.clone()] }),
+        }
+    }
+    // History is newest first, but pinning and promotion can reorder it
+    for group in &mut groups {
+        group.days.sort_by(|a, b| b.day.cmp(&a.day));
+    }
+    Ok(groups)
+}
+
+// UTC calendar date (YYYY-MM-DD) of a Unix timestamp
+fn utc_day(timestamp: u64) -> String {
+    // Days-to-civil conversion from Howard Hinnant's date algorithms
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Tauri command to report the most frequent words across history, ignoring
+// stopwords and words shorter than `min_len` characters. Ties are broken
+// alphabetically.
+#[tauri::command]
+async fn word_frequency(
+    top_n: usize,
+    min_len: usize,
+    history: State<'_, ClipboardHistory>
+) -> Result<Vec<(String, u64)>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for item in history_guard.iter().filter(|item| item.content_type == "text" && !item.hash_only) {
+        for word in item.content.split(|c: char| !c.is_alphanumeric() && c != '\'') {
+            let word = word.trim_matches('\'').to_lowercase();
+            if word.chars().count() < min_len.max(1) || STOPWORDS.contains(&word.as_str()) {
+                continue;
+            }
+            *counts.entry(word).or_insert(0) += 1;
+        }
+    }
+    drop(history_guard);
+
+    let mut words: Vec<(String, u64)> = counts.into_iter().collect();
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    words.truncate(top_n);
+    Ok(words)
+}
+
+// Tauri command to split history into sessions wherever consecutive captures
+// are more than `gap_secs` apart. Sessions are returned oldest first.
+#[tauri::command]
+async fn detect_sessions(gap_secs: u64, history: State<'_, ClipboardHistory>) -> Result<Vec<SessionRange>, String> {
+    let mut timestamps: Vec<u64> = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard.iter().map(|item| item.timestamp).collect()
+    };
+    timestamps.sort_unstable();
+
+    let mut sessions: Vec<SessionRange> = Vec::new();
+    for timestamp in timestamps {
+        match sessions.last_mut() {
+            Some(session) if timestamp - session.end <= gap_secs => {
+                session.end = timestamp;
+                session.item_count += 1;
+            }
+            _ => sessions.push(SessionRange { start: timestamp, end: timestamp, item_count: 1 }),
+        }
+    }
+    Ok(sessions)
+}
+
+// Tauri command to find the longest substring shared by all the given items.
+// Returns an empty string when they have nothing in common.
+#[tauri::command]
+async fn common_substring(ids: Vec<u64>, history: State<'_, ClipboardHistory>) -> Result<String, String> {
+    let contents: Vec<String> = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        ids.iter()
+            .map(|id| {
+                let item = history_guard
+                    .iter()
+                    .find(|item| item.id == *id)
+                    .ok_or_else(|| format!("No history item with id {}", id))?;
+                if item.hash_only {
+                    return Err(format!("Item {} only has its hash stored", id));
+                }
+                Ok(item.content.chars().take(COMMON_SUBSTRING_MAX_CHARS).collect())
+            })
+            .collect::<Result<_, String>>()?
+    };
+    let texts: Vec<&str> = contents.iter().map(String::as_str).collect();
+    Ok(common_substring::longest(&texts))
+}
+
+// Tauri command to add a tag to an item
+#[tauri::command]
+async fn tag_item(app: AppHandle, id: u64, tag: String, history: State<'_, ClipboardHistory>) -> Result<(), String> {
+    {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let item = history_guard
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("No history item with id {}", id))?;
+        if !item.tags.contains(&tag) {
+            item.tags.push(tag);
+        }
+    }
+    persist_history(&app);
+    Ok(())
+}
+
+// Tauri command to remove a tag from an item
+#[tauri::command]
+async fn untag_item(app: AppHandle, id: u64, tag: String, history: State<'_, ClipboardHistory>) -> Result<(), String> {
+    {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let item = history_guard
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("No history item with id {}", id))?;
+        item.tags.retain(|t| t != &tag);
+    }
+    persist_history(&app);
+    Ok(())
+}
+
+// Tauri command to set a metadata key on an item, replacing any existing value
+#[tauri::command]
+async fn set_item_metadata(
+    app: AppHandle,
+    id: u64,
+    key: String,
+    value: String,
+    history: State<'_, ClipboardHistory>
+) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("Metadata key must not be empty".to_string());
+    }
+    {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let item = history_guard
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("No history item with id {}", id))?;
+        item.metadata.insert(key, value);
+    }
+    persist_history(&app);
+    Ok(())
+}
+
+// Tauri command to get all metadata attached to an item
+#[tauri::command]
+async fn get_item_metadata(id: u64, history: State<'_, ClipboardHistory>) -> Result<HashMap<String, String>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    history_guard
+        .iter()
+        .find(|item| item.id == id)
+        .map(|item| item.metadata.clone())
+        .ok_or_else(|| format!("No history item with id {}", id))
+}
+
+// Tauri command to flag or unflag an item as a secret
+#[tauri::command]
+async fn mark_secret(app: AppHandle, id: u64, secret: bool, history: State<'_, ClipboardHistory>) -> Result<(), String> {
+    {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let item = history_guard
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("No history item with id {}", id))?;
+        item.secret = secret;
+    }
+    persist_history(&app);
+    Ok(())
+}
+
+// Tauri command to check existing history for likely secrets, returning the ids
+// found. With `auto_mark`, those items are also flagged as secret.
+#[tauri::command]
+async fn scan_for_secrets(
+    app: AppHandle,
+    auto_mark: Option<bool>,
+    history: State<'_, ClipboardHistory>
+) -> Result<Vec<u64>, String> {
+    let auto_mark = auto_mark.unwrap_or(false);
+    let (found, marked) = {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let mut found = Vec::new();
+        let mut marked = false;
+        // Hash-only items have no content left to scan
+        for item in history_guard.iter_mut().filter(|item| !item.hash_only) {
+            if secrets::looks_secret(&item.content) {
+                found.push(item.id);
+                if auto_mark && !item.secret {
+                    item.secret = true;
+                    marked = true;
+                }
+            }
+        }
+        (found, marked)
+    };
+    if marked {
+        persist_history(&app);
+    }
+    Ok(found)
+}
+
+// Tauri command to tag captured content matching a pattern automatically
+#[tauri::command]
+async fn add_auto_tag_rule(
+    app: AppHandle,
+    pattern: String,
+    tag: String,
+    rules: State<'_, AutoTagRules>
+) -> Result<(), String> {
+    let regex = Regex::new(&pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+    {
+        let mut rules_guard = rules.lock().map_err(|e| format!("Failed to lock auto-tag rules: {}", e))?;
+        rules_guard.push((AutoTagRule { pattern, tag }, regex));
+    }
+    persist_auto_tag_rules(&app);
+    Ok(())
+}
+
+// Tauri command to list the auto-tag rules
+#[tauri::command]
+async fn list_auto_tag_rules(rules: State<'_, AutoTagRules>) -> Result<Vec<AutoTagRule>, String> {
+    let rules_guard = rules.lock().map_err(|e| format!("Failed to lock auto-tag rules: {}", e))?;
+    Ok(rules_guard.iter().map(|(rule, _)| rule.clone()).collect())
+}
+
+// Tauri command to remove an auto-tag rule
+#[tauri::command]
+async fn remove_auto_tag_rule(
+    app: AppHandle,
+    pattern: String,
+    tag: String,
+    rules: State<'_, AutoTagRules>
+) -> Result<(), String> {
+    {
+        let mut rules_guard = rules.lock().map_err(|e| format!("Failed to lock auto-tag rules: {}", e))?;
+        rules_guard.retain(|(rule, _)| rule.pattern != pattern || rule.tag != tag);
+    }
+    persist_auto_tag_rules(&app);
+    Ok(())
+}
+
+// Tauri command to define (or replace) a named redaction ruleset. Built-in
+// ruleset names can't be redefined.
+#[tauri::command]
+async fn add_redaction_ruleset(
+    app: AppHandle,
+    name: String,
+    patterns: Vec<String>,
+    rulesets: State<'_, RedactionRulesets>
+) -> Result<(), String> {
+    let compiled = compile_redaction_rulesets(HashMap::from([(name, patterns)]))?;
+    {
+        let mut rulesets_guard = rulesets.lock().map_err(|e| format!("Failed to lock redaction rulesets: {}", e))?;
+        rulesets_guard.extend(compiled);
+    }
+    persist_redaction_rulesets(&app);
+    Ok(())
+}
+
+// Tauri command to list redaction ruleset names, built-in ones first
+#[tauri::command]
+async fn list_redaction_rulesets(rulesets: State<'_, RedactionRulesets>) -> Result<Vec<String>, String> {
+    let rulesets_guard = rulesets.lock().map_err(|e| format!("Failed to lock redaction rulesets: {}", e))?;
+    let mut custom: Vec<String> = rulesets_guard.keys().cloned().collect();
+    custom.sort();
+    Ok(redact::BUILTIN_NAMES.iter().map(|name| name.to_string()).chain(custom).collect())
+}
+
+// Tauri command to get an item's content with a redaction ruleset applied, for
+// sharing. The stored item is left untouched.
+#[tauri::command]
+async fn redact_item(
+    id: u64,
+    ruleset: String,
+    history: State<'_, ClipboardHistory>,
+    rulesets: State<'_, RedactionRulesets>
+) -> Result<String, String> {
+    let regexes = resolve_ruleset(&rulesets, &ruleset)?;
+
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let item = history_guard
+        .iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| format!("No history item with id {}", id))?;
+    if item.hash_only {
+        return Err(format!("Item {} only has its hash stored", id));
+    }
+    Ok(redact::apply(&item.content, &regexes))
+}
+
+// Helper to get the compiled patterns of a built-in or user-defined ruleset
+fn resolve_ruleset(rulesets: &RedactionRulesets, name: &str) -> Result<Vec<Regex>, String> {
+    match redact::builtin_patterns(name) {
+        Some(patterns) => patterns
+            .into_iter()
+            .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e)))
+            .collect(),
+        None => {
+            let rulesets_guard = rulesets.lock().map_err(|e| format!("Failed to lock redaction rulesets: {}", e))?;
+            rulesets_guard
+                .get(name)
+                .map(|(_, regexes)| regexes.clone())
+                .ok_or_else(|| format!("No redaction ruleset named '{}'", name))
+        }
+    }
+}
+
+// Tauri command to export history with transforms and then redaction rulesets
+// applied to each item's content. Only the exported copies are changed.
+// Returns the number of items written.
+#[tauri::command]
+async fn export_transformed(
+    path: String,
+    format: transform::ExportFormat,
+    transforms: Vec<transform::Transform>,
+    redactions: Vec<String>,
+    settings: State<'_, AppSettings>,
+    history: State<'_, ClipboardHistory>,
+    rulesets: State<'_, RedactionRulesets>
+) -> Result<usize, String> {
+    let mut regexes = Vec::new();
+    for name in &redactions {
+        regexes.extend(resolve_ruleset(&rulesets, name)?);
+    }
+
+    let settings = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?.clone();
+    let items: Vec<ClipboardItem> = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard
+            .iter()
+            .map(|item| {
+                let mut item = item.clone();
+                if !item.hash_only {
+                    item.content = redact::apply(&transform::apply_all(&item.content, &transforms), &regexes);
+                    // Everything derived from the original content would leak it
+                    // (a hash lets anyone confirm guesses of masked text), so
+                    // recompute it from the exported content
+                    item.content_hash = hash_content(&item.content);
+                    item.cid = content_id(&item.content_hash);
+                    item.content_length = item.content.len();
+                    item.preview = preview_for(&item, &settings);
+                }
+                item
+            })
+            .collect()
+    };
+
+    let path = PathBuf::from(path);
+    match format {
+        transform::ExportFormat::Json => {
+            storage::write_json(&path, &items)?;
+            Ok(items.len())
+        }
+        transform::ExportFormat::Text => {
+            // Hash-only items have no content to write
+            let contents: Vec<&str> = items
+                .iter()
+                .filter(|item| !item.hash_only)
+                .map(|item| item.content.as_str())
+                .collect();
+            fs::write(&path, contents.join("\n\n")).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            Ok(contents.len())
+        }
+    }
+}
+
+// Tauri command to start appending captures to one accumulator item instead of
+// creating new entries. Without a target id, the next capture becomes the accumulator.
+#[tauri::command]
+async fn enable_append_mode(
+    target_id: Option<u64>,
+    separator: Option<String>,
+    history: State<'_, ClipboardHistory>,
+    append_mode: State<'_, AppendMode>
+) -> Result<(), String> {
+    if let Some(id) = target_id {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let target = history_guard
+            .iter()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("No history item with id {}", id))?;
+        if target.hash_only {
+            return Err(format!("Item {} only has its hash stored and can't be appended to", id));
+        }
+    }
+
+    let mut append_guard = append_mode.lock().map_err(|e| format!("Failed to lock append mode: {}", e))?;
+    *append_guard = Some(AppendModeState {
+        target_id,
+        separator: separator.unwrap_or_else(|| "\n".to_string()),
+    });
+    Ok(())
+}
+
+// Tauri command to go back to creating a new entry per capture
+#[tauri::command]
+async fn disable_append_mode(append_mode: State<'_, AppendMode>) -> Result<(), String> {
+    let mut append_guard = append_mode.lock().map_err(|e| format!("Failed to lock append mode: {}", e))?;
+    *append_guard = None;
+    Ok(())
+}
+
+// Tauri command to get n random items from history (without replacement).
+// Passing a seed makes the sample deterministic.
+#[tauri::command]
+async fn sample_history(
+    n: usize,
+    seed: Option<u64>,
+    history: State<'_, ClipboardHistory>
+) -> Result<Vec<ClipboardItem>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    // Asking for more than we have just returns everything in random order
+    let amount = n.min(history_guard.len());
+    let indices = rand::seq::index::sample(&mut rng, history_guard.len(), amount);
+    Ok(indices.into_iter().map(|i| history_guard[i].clone()).collect())
+}
+
+// Tauri command to get the newest item from each distinct source app.
+// Items without a known source app are left out.
+#[tauri::command]
+async fn latest_per_source(history: State<'_, ClipboardHistory>) -> Result<Vec<ClipboardItem>, String> {
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let mut seen_sources = HashSet::new();
+    // History is newest first, so the first item per source is its latest
+    Ok(history_guard
+        .iter()
+        .filter(|item| match &item.source_app {
+            Some(source) => seen_sources.insert(source.clone()),
+            None => false,
+        })
+        .cloned()
+        .collect())
+}
+
+// Tauri command to add item to clipboard history manually (for testing)
+#[tauri::command]
+async fn add_to_history(app: AppHandle, content: String) -> Result<(), String> {
+    add_item_to_history(&app, &content, None, None).map(|_| ())
+}
+
+// Helper function to add items to history (used by both manual and automatic monitoring).
+// Returns the stored item, or None when the content was skipped.
+fn add_item_to_history(
+    app: &AppHandle,
+    content: &str,
+    source_app: Option<String>,
+    display: Option<u32>
+) -> Result<Option<ClipboardItem>, String> {
+    capture_item(app, content, source_app, display, CaptureMode::Live)
+}
+
+// How content reaches history
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CaptureMode {
+    // Clipboard, manual and ingest captures, with every side effect
+    Live,
+    // Bulk imports: filters and dedup still apply, but there's no append
+    // routing, PII event, audit entry or webhook per item
+    Import,
+}
+
+fn capture_item(
+    app: &AppHandle,
+    content: &str,
+    source_app: Option<String>,
+    display: Option<u32>,
+    mode: CaptureMode
+) -> Result<Option<ClipboardItem>, String> {
+    if content.trim().is_empty() {
+        record_metric(app, |m| m.total_skipped_empty += 1);
+        return Ok(None); // Don't add empty content
+    }
+
+    let settings = app.state::<AppSettings>()
+        .lock()
+        .map_err(|e| format!("Failed to lock settings: {}", e))?
+        .clone();
+
+    let passes = {
+        let filters = app.state::<CaptureFilters>();
+        let filters_guard = filters.lock().map_err(|e| format!("Failed to lock capture filters: {}", e))?;
+        passes_capture_filters(content, &filters_guard)
+    };
+    if !passes {
+        record_metric(app, |m| m.total_skipped_filtered += 1);
+        return Ok(None);
+    }
+
+    // A failing expression counts as a rejection rather than letting content
+    // through; the frontend is told so it doesn't fail silently
+    let accepted = {
+        let filters = app.state::<CaptureFilters>();
+        let filters_guard = filters.lock().map_err(|e| format!("Failed to lock capture filters: {}", e))?;
+        filters_guard.capture_expression.as_ref().map(|tree| {
+            capture_rule::evaluate(tree, content, "text", source_app.as_deref()).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                if let Err(emit_error) = app.emit("capture-expression-error", &e) {
+                    eprintln!("Failed to emit capture expression error: {}", emit_error);
+                }
+                false
+            })
+        })
+    };
+    if accepted == Some(false) {
+        record_metric(app, |m| m.total_skipped_filtered += 1);
+        return Ok(None);
+    }
+
+    // Warn about PII by type only, and optionally refuse to store card numbers
+    if settings.warn_on_pii || settings.skip_card_numbers {
+        let kinds = pii::detect(content);
+        if settings.warn_on_pii && mode == CaptureMode::Live && !kinds.is_empty() {
+            if let Err(e) = app.emit("pii-detected", &kinds) {
+                eprintln!("Failed to emit PII event: {}", e);
+            }
+        }
+        if settings.skip_card_numbers && kinds.contains(&pii::CREDIT_CARD) {
+            record_metric(app, |m| m.total_skipped_filtered += 1);
+            return Ok(None);
+        }
+    }
+
+    // Tags from any auto-tag rules the content matches
+    let mut tags: Vec<String> = Vec::new();
+    {
+        let rules = app.state::<AutoTagRules>();
+        let rules_guard = rules.lock().map_err(|e| format!("Failed to lock auto-tag rules: {}", e))?;
+        for (rule, regex) in rules_guard.iter() {
+            if regex.is_match(content) && !tags.contains(&rule.tag) {
+                tags.push(rule.tag.clone());
+            }
+        }
+    }
+
+    // Content matching a privacy pattern is kept only as its keyed hash and length
+    let hash_only = app
+        .state::<CaptureFilters>()
+        .lock()
+        .map_err(|e| format!("Failed to lock capture filters: {}", e))?
+        .hash_only
+        .iter()
+        .any(|re| re.is_match(content));
+    let hash_key = app
+        .state::<HashKey>()
+        .lock()
+        .map_err(|e| format!("Failed to lock hash key: {}", e))?
+        .clone();
+    let content_hash = if hash_only { keyed_hash(&hash_key, content) } else { hash_content(content) };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    // Hash-only content is never appended, since that would expose it
+    let append_mode = if hash_only || mode == CaptureMode::Import {
+        None
+    } else {
+        app.state::<AppendMode>()
+            .lock()
+            .map_err(|e| format!("Failed to lock append mode: {}", e))?
+            .clone()
+    };
+    
+    let item = {
+        let history = app.state::<ClipboardHistory>();
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+
+        // While append mode is on, captures are concatenated onto the accumulator
+        if let Some(accumulator) = append_to_accumulator(&mut history_guard, append_mode.as_ref(), content, &tags, &settings) {
+            if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+                index_guard.insert(accumulator.id, &accumulator.content);
+            }
+            drop(history_guard);
+            // The audit log and webhook describe this capture, not the whole buffer
+            let snippet = ClipboardItem {
+                content: content.to_string(),
+                timestamp,
+                cid: content_id(&content_hash),
+                content_hash,
+                content_length: content.len(),
+                preview: make_preview(content),
+                ..accumulator.clone()
+            };
+            after_capture(app, &snippet, &settings, mode);
+            return Ok(Some(accumulator));
+        }
+        
+        // Check if this content is already the most recent item (avoid duplicates)
+        if let Some(latest) = history_guard.front_mut() {
+            let is_duplicate = if latest.hash_only {
+                latest.content_hash == keyed_hash(&hash_key, content)
+            } else {
+                dedup_key(&latest.content, &settings) == dedup_key(content, &settings)
+            };
+            if is_duplicate {
+                record_metric(app, |m| m.total_skipped_duplicate += 1);
+                // Don't add duplicate, but count the repeat unless it's just noise
+                let last_seen = latest.last_used.unwrap_or(latest.timestamp);
+                let too_soon = settings
+                    .min_repeat_interval_secs
+                    .is_some_and(|interval| timestamp.saturating_sub(last_seen) < interval);
+                if !too_soon {
+                    latest.occurrence_count += 1;
+                    latest.last_used = Some(timestamp);
+                    drop(history_guard);
+                    persist_history(app);
+                }
+                return Ok(None);
+            }
+        }
+        
+        // Generate a simple ID based on timestamp and length, stepping past
+        // ids already taken by captures in the same second
+        let mut id = timestamp + history_guard.len() as u64;
+        while history_guard.iter().any(|existing| existing.id == id) {
+            id += 1;
+        }
+        
+        let mut item = ClipboardItem {
+            id,
+            content: if hash_only { String::new() } else { content.to_string() },
+            timestamp,
+            content_type: "text".to_string(),
+            source_app,
+            cid: if hash_only { keyed_content_id(&content_hash) } else { content_id(&content_hash) },
+            content_hash,
+            content_length: content.len(),
+            hash_only,
+            tags,
+            pinned: false,
+            metadata: HashMap::new(),
+            display,
+            last_used: None,
+            occurrence_count: 1,
+            secret: false,
+            preview: String::new(),
+        };
+        item.preview = preview_for(&item, &settings);
+        
+        // Add to front of deque (newest first)
+        history_guard.push_front(item.clone());
+        
+        // Keep only the last MAX_HISTORY_ITEMS, dropping the oldest unpinned one
+        let mut trimmed = None;
+        if history_guard.len() > MAX_HISTORY_ITEMS {
+            if let Some(oldest_unpinned) = history_guard.iter().rposition(|item| !item.pinned) {
+                trimmed = history_guard.remove(oldest_unpinned);
+            }
+        }
+
+        if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+            index_guard.insert(item.id, &item.content);
+            if let Some(trimmed) = trimmed {
+                index_guard.remove(trimmed.id);
+            }
+        }
+
+        item
+    };
+
+    // In append mode without a live accumulator, this item becomes the accumulator
+    if append_mode.is_some() {
+        let append_state = app.state::<AppendMode>();
+        let mut append_guard = append_state.lock().map_err(|e| format!("Failed to lock append mode: {}", e))?;
+        if let Some(mode) = append_guard.as_mut() {
+            mode.target_id = Some(item.id);
+        }
+    }
+
+    after_capture(app, &item, &settings, mode);
+    Ok(Some(item))
+}
+
+// Tell the frontend a new item was captured
+fn emit_clipboard_updated(app: &AppHandle, item: ClipboardItem) {
+    let max_payload = app.state::<AppSettings>()
+        .lock()
+        .map(|s| s.max_emit_payload_bytes)
+        .unwrap_or(None);
+    let payload = ClipboardUpdatedPayload::new(item, max_payload);
+    if let Err(e) = app.emit("clipboard-updated", &payload) {
+        eprintln!("Failed to emit clipboard update event: {}", e);
+    }
+}
+
+// Side effects of a successful capture (new item or append), run without locks held
+fn after_capture(app: &AppHandle, item: &ClipboardItem, settings: &Settings, mode: CaptureMode) {
+    record_metric(app, |m| m.total_captured += 1);
+    persist_history(app);
+    if mode == CaptureMode::Import {
+        return;
+    }
+
+    // Record the capture in the audit log; a failed write shouldn't lose the item
+    if let Err(e) = append_audit_entry(app, item, settings) {
+        eprintln!("Failed to append audit entry: {}", e);
+    }
+
+    if let Some(url) = &settings.capture_webhook_url {
+        webhook::deliver(url.clone(), webhook::WebhookPayload::new(item, settings.webhook_include_content));
+    }
+}
+
+// Helper to bump a capture counter
+fn record_metric(app: &AppHandle, update: impl FnOnce(&mut CaptureMetrics)) {
+    if let Ok(mut metrics_guard) = app.state::<Metrics>().lock() {
+        update(&mut metrics_guard);
+    }
+}
+
+// Helper to append content to the append-mode accumulator. Returns the updated
+// accumulator, or None when there's no accumulator to append to.
+fn append_to_accumulator(
+    history: &mut VecDeque<ClipboardItem>,
+    mode: Option<&AppendModeState>,
+    content: &str,
+    tags: &[String],
+    settings: &Settings
+) -> Option<ClipboardItem> {
+    let mode = mode?;
+    let target_id = mode.target_id?;
+    let target = history.iter_mut().find(|item| item.id == target_id && !item.hash_only)?;
+
+    target.content.push_str(&mode.separator);
+    target.content.push_str(content);
+    target.content_hash = hash_content(&target.content);
+    target.cid = content_id(&target.content_hash);
+    target.content_length = target.content.len();
+    target.preview = preview_for(target, settings);
+    for tag in tags {
+        if !target.tags.contains(tag) {
+            target.tags.push(tag.clone());
+        }
+    }
+    Some(target.clone())
+}
+
+// Short single-line preview of the content
+fn make_preview(content: &str) -> String {
+    let first_line = content.lines().next().unwrap_or("");
+    let mut preview: String = first_line.chars().take(PREVIEW_CHARS).collect();
+    if preview.len() < content.len() {
+        preview.push('…');
+    }
+    preview
+}
+
+// Preview of at most `limit` characters, keeping line breaks
+fn make_preview_with_limit(content: &str, limit: usize) -> String {
+    let mut preview: String = content.chars().take(limit).collect();
+    if preview.len() < content.len() {
+        preview.push('…');
+    }
+    preview
+}
+
+// Helper to compute the stored preview of an item from its content type
+fn preview_for(item: &ClipboardItem, settings: &Settings) -> String {
+    match settings.preview_lengths.get(&item.content_type) {
+        Some(limit) => make_preview_with_limit(&item.content, *limit),
+        None => make_preview(&item.content),
+    }
+}
+
+// Helper to recompute stored previews, e.g. after preview_lengths changes
+fn refresh_previews<'a>(items: impl IntoIterator<Item = &'a mut ClipboardItem>, settings: &Settings) {
+    for item in items {
+        item.preview = preview_for(item, settings);
+    }
+}
+
+// Directory where CopyMate keeps its files, created on first use
+fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("Failed to resolve data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    Ok(dir)
+}
+
+// Helper to write a file into the data directory, recording whether it worked
+fn write_data_file<T: Serialize + ?Sized>(app: &AppHandle, file_name: &str, value: &T) -> Result<(), String> {
+    let result = data_dir(app).and_then(|dir| storage::write_json(&dir.join(file_name), value));
+    if let Ok(mut status_guard) = app.state::<Persistence>().lock() {
+        status_guard.last_write_ok = result.is_ok();
+    }
+    result
+}
+
+// Mark history as changed and schedule a write, at most one per debounce period
+fn persist_history(app: &AppHandle) {
+    let debounce = app
+        .state::<AppSettings>()
+        .lock()
+        .ok()
+        .and_then(|s| s.persist_debounce_ms)
+        .unwrap_or(DEFAULT_PERSIST_DEBOUNCE_MS);
+
+    let delay = {
+        let writer = app.state::<HistoryWriter>();
+        let mut writer_guard = match writer.lock() {
+            Ok(writer_guard) => writer_guard,
+            Err(e) => {
+                eprintln!("Failed to lock history writer: {}", e);
+                return;
+            }
+        };
+        writer_guard.dirty = true;
+        if writer_guard.write_scheduled {
+            return;
+        }
+        writer_guard.write_scheduled = true;
+        let since_last = writer_guard.last_write.map_or(Duration::MAX, |written| written.elapsed());
+        Duration::from_millis(debounce).saturating_sub(since_last)
+    };
+
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(delay);
+        if let Err(e) = flush_history(&app) {
+            eprintln!("Failed to save history: {}", e);
+        }
+    });
+}
+
+// Write history to disk now if it has unsaved changes. The writer lock is held
+// for the whole write so writes never overlap.
+fn flush_history(app: &AppHandle) -> Result<(), String> {
+    let writer = app.state::<HistoryWriter>();
+    let mut writer_guard = writer.lock().map_err(|e| format!("Failed to lock history writer: {}", e))?;
+    writer_guard.write_scheduled = false;
+    if !writer_guard.dirty {
+        return Ok(());
+    }
+    writer_guard.dirty = false;
+
+    let items: Vec<ClipboardItem> = {
+        let history = app.state::<ClipboardHistory>();
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard.iter().cloned().collect()
+    };
+    writer_guard.last_write = Some(Instant::now());
+    if let Err(e) = write_data_file(app, storage::HISTORY_FILE, &items) {
+        // Keep the changes pending so the next flush retries them
+        writer_guard.dirty = true;
+        return Err(e);
+    }
+    writer_guard.last_write_ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|elapsed| elapsed.as_secs());
+    Ok(())
+}
+
+// Tauri command to compare history's memory footprint with its size on disk
+#[tauri::command]
+async fn get_storage_report(app: AppHandle, history: State<'_, ClipboardHistory>) -> Result<StorageReport, String> {
+    let (in_memory_bytes, item_count) = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let bytes = history_guard
+            .iter()
+            .map(|item| {
+                let strings = [&item.content, &item.content_type, &item.content_hash, &item.cid, &item.preview]
+                    .iter()
+                    .map(|s| s.capacity())
+                    .sum::<usize>();
+                let tags = item.tags.iter().map(|tag| tag.capacity()).sum::<usize>();
+                let metadata = item.metadata.iter().map(|(k, v)| k.capacity() + v.capacity()).sum::<usize>();
+                let source = item.source_app.as_ref().map_or(0, |s| s.capacity());
+                std::mem::size_of::<ClipboardItem>() + strings + tags + metadata + source
+            })
+            .sum();
+        (bytes, history_guard.len())
+    };
+
+    let path = data_dir(&app)?.join(storage::HISTORY_FILE);
+    let on_disk_bytes = match fs::metadata(&path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    Ok(StorageReport {
+        in_memory_bytes,
+        on_disk_bytes,
+        item_count,
+        compressed: false,
+    })
+}
+
+// Tauri command to write pending history changes to disk immediately
+#[tauri::command]
+async fn flush_now(app: AppHandle) -> Result<(), String> {
+    flush_history(&app)
+}
+
+// Tauri command to report the state of the debounced history writer
+#[tauri::command]
+async fn get_persist_stats(writer: State<'_, HistoryWriter>) -> Result<PersistStats, String> {
+    let writer_guard = writer.lock().map_err(|e| format!("Failed to lock history writer: {}", e))?;
+    Ok(PersistStats {
+        pending: writer_guard.dirty,
+        last_write_ts: writer_guard.last_write_ts,
+    })
+}
+
+// Save the current settings to disk, logging failures
+fn persist_settings(app: &AppHandle) {
+    let settings = match app.state::<AppSettings>().lock() {
+        Ok(settings_guard) => settings_guard.clone(),
+        Err(e) => {
+            eprintln!("Failed to lock settings: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = write_data_file(app, storage::SETTINGS_FILE, &settings) {
+        eprintln!("Failed to save settings: {}", e);
+    }
+}
+
+// Save the inactive workspaces and which one is active, logging failures
+fn persist_workspaces(app: &AppHandle) {
+    let store = match app.state::<Workspaces>().lock() {
+        Ok(workspaces_guard) => workspaces_guard.clone(),
+        Err(e) => {
+            eprintln!("Failed to lock workspaces: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = write_data_file(app, storage::WORKSPACES_FILE, &store) {
+        eprintln!("Failed to save workspaces: {}", e);
+    }
+}
+
+// Save the quick slot assignments, logging failures
+fn persist_slots(app: &AppHandle) {
+    let slots = match app.state::<QuickSlots>().lock() {
+        Ok(slots_guard) => *slots_guard,
+        Err(e) => {
+            eprintln!("Failed to lock quick slots: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = write_data_file(app, storage::SLOTS_FILE, &slots) {
+        eprintln!("Failed to save quick slots: {}", e);
+    }
+}
+
+// Save the pinned section's order, logging failures
+fn persist_pin_order(app: &AppHandle) {
+    let pin_order = match app.state::<PinOrder>().lock() {
+        Ok(pin_order_guard) => pin_order_guard.clone(),
+        Err(e) => {
+            eprintln!("Failed to lock pin order: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = write_data_file(app, storage::PIN_ORDER_FILE, &pin_order) {
+        eprintln!("Failed to save pin order: {}", e);
+    }
+}
+
+// Save the auto-tag rules, logging failures
+fn persist_auto_tag_rules(app: &AppHandle) {
+    let rules: Vec<AutoTagRule> = match app.state::<AutoTagRules>().lock() {
+        Ok(rules_guard) => rules_guard.iter().map(|(rule, _)| rule.clone()).collect(),
+        Err(e) => {
+            eprintln!("Failed to lock auto-tag rules: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = write_data_file(app, storage::AUTO_TAG_RULES_FILE, &rules) {
+        eprintln!("Failed to save auto-tag rules: {}", e);
+    }
+}
+
+// Save the user-defined redaction rulesets as patterns, logging failures
+fn persist_redaction_rulesets(app: &AppHandle) {
+    let rulesets: HashMap<String, Vec<String>> = match app.state::<RedactionRulesets>().lock() {
+        Ok(rulesets_guard) => rulesets_guard
+            .iter()
+            .map(|(name, (patterns, _))| (name.clone(), patterns.clone()))
+            .collect(),
+        Err(e) => {
+            eprintln!("Failed to lock redaction rulesets: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = write_data_file(app, storage::REDACTION_RULESETS_FILE, &rulesets) {
+        eprintln!("Failed to save redaction rulesets: {}", e);
+    }
+}
+
+// Save the saved searches, logging failures
+fn persist_saved_searches(app: &AppHandle) {
+    let searches = match app.state::<SavedSearches>().lock() {
+        Ok(searches_guard) => searches_guard.clone(),
+        Err(e) => {
+            eprintln!("Failed to lock saved searches: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = write_data_file(app, storage::SEARCHES_FILE, &searches) {
+        eprintln!("Failed to save saved searches: {}", e);
+    }
+}
+
+// Load saved history and settings, then check the data directory accepts writes.
+// If it doesn't, the problem is recorded in the persistence status; this runs
+// before the window can listen for events, so the `persistence-warning` event
+// is sent later by emit_persistence_warning.
+fn init_persistence(app: &AppHandle) {
+    let dir = match data_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            record_persistence_unavailable(app, &e);
+            return;
+        }
+    };
+
+    // Load the hash key before anything is captured, saving the in-memory one
+    // on first run
+    match storage::read_json::<Vec<u8>>(&dir.join(storage::HASH_KEY_FILE)) {
+        Ok(Some(key)) if !key.is_empty() => {
+            if let Ok(mut hash_key_guard) = app.state::<HashKey>().lock() {
+                *hash_key_guard = key;
+            }
+        }
+        Ok(_) => {
+            let hash_key = app.state::<HashKey>();
+            let key = hash_key.lock().map(|key| key.clone());
+            if let Ok(key) = key {
+                if let Err(e) = write_data_file(app, storage::HASH_KEY_FILE, &key) {
+                    eprintln!("Failed to save hash key: {}", e);
+                }
+            }
+        }
+        Err(e) => eprintln!("Failed to load hash key: {}", e),
+    }
+    match storage::read_json::<Vec<ClipboardItem>>(&dir.join(storage::HISTORY_FILE)) {
+        Ok(Some(mut items)) => {
+            backfill_cids(&mut items);
+            if let Ok(mut history_guard) = app.state::<ClipboardHistory>().lock() {
+                *history_guard = items.into();
+                if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+                    index_guard.rebuild(history_guard.iter());
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load history: {}", e),
+    }
+    match storage::read_json::<WorkspaceStore>(&dir.join(storage::WORKSPACES_FILE)) {
+        Ok(Some(mut store)) => {
+            backfill_cids(store.inactive.values_mut().flatten());
+            if let Ok(mut workspaces_guard) = app.state::<Workspaces>().lock() {
+                *workspaces_guard = store;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load workspaces: {}", e),
+    }
+    match storage::read_json::<[Option<u64>; 9]>(&dir.join(storage::SLOTS_FILE)) {
+        Ok(Some(slots)) => {
+            if let Ok(mut slots_guard) = app.state::<QuickSlots>().lock() {
+                *slots_guard = slots;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load quick slots: {}", e),
+    }
+    match storage::read_json::<Vec<u64>>(&dir.join(storage::PIN_ORDER_FILE)) {
+        Ok(Some(pin_order)) => {
+            if let Ok(mut pin_order_guard) = app.state::<PinOrder>().lock() {
+                *pin_order_guard = pin_order;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load pin order: {}", e),
+    }
+    match storage::read_json::<Vec<AutoTagRule>>(&dir.join(storage::AUTO_TAG_RULES_FILE))
+        .and_then(|rules| rules.map(compile_auto_tag_rules).transpose())
+    {
+        Ok(Some(rules)) => {
+            if let Ok(mut rules_guard) = app.state::<AutoTagRules>().lock() {
+                *rules_guard = rules;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load auto-tag rules: {}", e),
+    }
+    match storage::read_json::<HashMap<String, Vec<String>>>(&dir.join(storage::REDACTION_RULESETS_FILE))
+        .and_then(|rulesets| rulesets.map(compile_redaction_rulesets).transpose())
+    {
+        Ok(Some(rulesets)) => {
+            if let Ok(mut rulesets_guard) = app.state::<RedactionRulesets>().lock() {
+                *rulesets_guard = rulesets;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load redaction rulesets: {}", e),
+    }
+    match storage::read_json::<Vec<SavedSearch>>(&dir.join(storage::SEARCHES_FILE)) {
+        Ok(Some(searches)) => {
+            if let Ok(mut searches_guard) = app.state::<SavedSearches>().lock() {
+                *searches_guard = searches;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load saved searches: {}", e),
+    }
+    match storage::read_json::<Settings>(&dir.join(storage::SETTINGS_FILE)) {
+        Ok(Some(mut settings)) => {
+            repair_settings(&mut settings);
+            if let Ok(mut settings_guard) = app.state::<AppSettings>().lock() {
+                *settings_guard = settings;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load settings: {}", e),
+    }
+    if let Err(e) = refresh_capture_filters(app) {
+        eprintln!("Failed to compile capture filters: {}", e);
+    }
+    // Previews depend on settings, so they're computed once everything is loaded
+    if let Ok(settings_guard) = app.state::<AppSettings>().lock() {
+        if let Ok(mut workspaces_guard) = app.state::<Workspaces>().lock() {
+            refresh_previews(workspaces_guard.inactive.values_mut().flatten(), &settings_guard);
+        }
+        if let Ok(mut history_guard) = app.state::<ClipboardHistory>().lock() {
+            refresh_previews(history_guard.iter_mut(), &settings_guard);
+        }
+    }
+
+    let writable = storage::check_writable(&dir);
+    if let Ok(mut status_guard) = app.state::<Persistence>().lock() {
+        *status_guard = PersistenceStatus {
+            writable: writable.is_ok(),
+            path: dir.display().to_string(),
+            last_write_ok: writable.is_ok(),
+            error: None,
+        };
+    }
+    if let Err(e) = writable {
+        record_persistence_unavailable(app, &e);
+    }
+}
+
+fn record_persistence_unavailable(app: &AppHandle, error: &str) {
+    eprintln!("Persistence unavailable, history will not be saved: {}", error);
+    if let Ok(mut status_guard) = app.state::<Persistence>().lock() {
+        status_guard.writable = false;
+        status_guard.last_write_ok = false;
+        status_guard.error = Some(error.to_string());
+    }
+}
+
+// Helper to send `persistence-warning` if history can't be saved. Called once
+// the frontend is listening (when it starts monitoring); frontends should also
+// call get_persistence_status at startup in case they attach listeners late.
+fn emit_persistence_warning(app: &AppHandle) {
+    let error = match app.state::<Persistence>().lock() {
+        Ok(status_guard) => status_guard.error.clone(),
+        Err(_) => None,
+    };
+    if let Some(error) = error {
+        if let Err(e) = app.emit("persistence-warning", error) {
+            eprintln!("Failed to emit persistence warning: {}", e);
+        }
+    }
+}
+
+// Helper to log a stored item to the audit log
+fn append_audit_entry(app: &AppHandle, item: &ClipboardItem, settings: &Settings) -> Result<(), String> {
+    let entry = audit::AuditEntry {
+        timestamp: item.timestamp,
+        content_hash: item.content_hash.clone(),
+        length: item.content_length,
+        content_type: item.content_type.clone(),
+        // Hash-only items never have their content written anywhere
+        content: if settings.audit_include_content && !item.hash_only {
+            Some(item.content.clone())
+        } else {
+            None
+        },
+    };
+    audit::append(&data_dir(app)?.join(audit::FILE_NAME), &entry)
+}
+
+// Whether content passes the allowlist/blocklist. A non-empty allowlist takes
+// precedence: content it matches is captured even if the blocklist matches too.
+fn passes_capture_filters(content: &str, filters: &CompiledFilters) -> bool {
+    if !filters.allowlist.is_empty() {
+        return filters.allowlist.iter().any(|re| re.is_match(content));
+    }
+    !filters.blocklist.iter().any(|re| re.is_match(content))
+}
+
+// Helper to recompile the capture filters after the settings' patterns change
+fn refresh_capture_filters(app: &AppHandle) -> Result<(), String> {
+    let compiled = {
+        let settings = app.state::<AppSettings>();
+        let settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        CompiledFilters::compile(&settings_guard)?
+    };
+    let filters = app.state::<CaptureFilters>();
+    let mut filters_guard = filters.lock().map_err(|e| format!("Failed to lock capture filters: {}", e))?;
+    *filters_guard = compiled;
+    Ok(())
+}
+
+// Key used to compare content for duplicates, normalized per the dedup settings
+fn dedup_key(content: &str, settings: &Settings) -> String {
+    let key = if settings.dedup_ignore_whitespace {
+        content.split_whitespace().collect::<Vec<_>>().join(" ")
+    } else {
+        content.to_string()
+    };
+    if settings.dedup_case_insensitive {
+        key.to_lowercase()
+    } else {
+        key
+    }
+}
+
+// Hex-encoded SHA-256 of the content
+fn hash_content(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Helper to HMAC-SHA256 hash-only content under the install's key
+fn keyed_hash(key: &[u8], content: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(content.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Helper to make a random key for a new install
+fn generate_hash_key() -> Vec<u8> {
+    use rand::RngCore;
+    let mut key = vec![0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+// Compile user-supplied regex patterns, failing on the first invalid one
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e)))
+        .collect()
+}
+
+// Tauri command to start clipboard monitoring
+#[tauri::command]
+async fn start_clipboard_monitoring(
+    app: AppHandle,
+    self_writes: State<'_, SelfWrites>,
+    settings: State<'_, AppSettings>,
+    paused: State<'_, CapturePaused>,
+    read_latency: State<'_, ReadLatency>,
+    backend: State<'_, MonitorBackend>
+) -> Result<(), String> {
+    // The window is listening by now, unlike when persistence was initialised
+    emit_persistence_warning(&app);
+
+    let app_clone = app.clone();
+    let self_writes_clone = self_writes.inner().clone();
+    let settings_clone = settings.inner().clone();
+    let paused_clone = paused.inner().clone();
+    let read_latency_clone = read_latency.inner().clone();
+    let backend_clone = backend.inner().clone();
+    
+    // Spawn background thread for clipboard monitoring
+    thread::spawn(move || {
+        let mut clipboard_watcher = watcher::create();
+        if let Ok(mut backend_guard) = backend_clone.lock() {
+            *backend_guard = Some(clipboard_watcher.name());
+        }
+
+        let mut last_clipboard_content = String::new();
+        let mut screenshare_active = false;
+        let mut last_screenshare_check: Option<Instant> = None;
+        let mut idle_paused = false;
+        let mut last_idle_check: Option<Instant> = None;
+        
+        loop {
+            // Wait for the clipboard to change, waking up every tick regardless.
+            // While idle-paused, just sleep instead of watching the clipboard.
+            let changed = if idle_paused {
+                thread::sleep(MONITOR_TICK);
+                false
+            } else {
+                clipboard_watcher.wait_for_change(MONITOR_TICK)
+            };
+
+            // Pause while the machine is idle and resume on activity
+            let (idle_threshold, self_write_expiry) = settings_clone
+                .lock()
+                .map(|s| (s.auto_pause_when_idle_secs, s.self_write_expiry_secs))
+                .unwrap_or_default();
+
+            // Forget self-writes that never showed up as a clipboard change
+            if let Ok(mut self_writes_guard) = self_writes_clone.lock() {
+                self_writes_guard.sweep(Duration::from_secs(
+                    self_write_expiry.unwrap_or(DEFAULT_SELF_WRITE_EXPIRY_SECS),
+                ));
+            }
+            let now_idle = match idle_threshold {
+                Some(threshold) => {
+                    let check_due = last_idle_check
+                        .is_none_or(|checked| checked.elapsed() >= IDLE_CHECK_INTERVAL);
+                    if check_due {
+                        last_idle_check = Some(Instant::now());
+                        // Platforms without detection never auto-pause
+                        platform::idle_seconds().is_some_and(|idle| idle >= threshold)
+                    } else {
+                        idle_paused
+                    }
+                }
+                None => false,
+            };
+            if now_idle != idle_paused {
+                idle_paused = now_idle;
+                if !idle_paused {
+                    last_idle_check = None;
+                }
+                if let Err(e) = app_clone.emit("idle-pause-changed", idle_paused) {
+                    eprintln!("Failed to emit idle pause event: {}", e);
+                }
+            }
+
+            // Periodically check for screen sharing when the setting is on
+            let pause_during_screenshare = settings_clone
+                .lock()
+                .map(|s| s.pause_during_screenshare)
+                .unwrap_or(false);
+            if pause_during_screenshare {
+                let check_due = last_screenshare_check
+                    .is_none_or(|checked| checked.elapsed() >= SCREENSHARE_CHECK_INTERVAL);
+                if check_due {
+                    last_screenshare_check = Some(Instant::now());
+                    // Platforms without detection leave the state untouched
+                    if let Some(active) = platform::is_screen_sharing() {
+                        if active != screenshare_active {
+                            screenshare_active = active;
+                            if let Err(e) = app_clone.emit("screenshare-pause-changed", active) {
+                                eprintln!("Failed to emit screenshare pause event: {}", e);
+                            }
+                        }
+                    }
+                }
+            } else if screenshare_active {
+                screenshare_active = false;
+                last_screenshare_check = None;
+                if let Err(e) = app_clone.emit("screenshare-pause-changed", false) {
+                    eprintln!("Failed to emit screenshare pause event: {}", e);
+                }
+            }
+
+            if !changed {
+                continue;
+            }
+
+            let is_paused = paused_clone.lock().map(|p| *p).unwrap_or(false) || screenshare_active;
+            
+            // Get current clipboard content, timing how long the read takes
+            let read_started = Instant::now();
+            let read_result = app_clone.clipboard().read_text();
+            if let Ok(mut latency_guard) = read_latency_clone.lock() {
+                latency_guard.record(read_started.elapsed());
+            }
+
+            if let Ok(current_content) = read_result {
+                // Polling reports a change every tick, so there only content
+                // that differs from the last read counts as a clipboard event.
+                // Other watchers pass repeat copies through so the duplicate
+                // interval and occurrence count apply to them.
+                let clipboard_event = clipboard_watcher.reports_real_changes()
+                    || current_content != last_clipboard_content;
+
+                // While paused, remember the content so it isn't captured on resume
+                if is_paused {
+                    if clipboard_event {
+                        record_metric(&app_clone, |m| m.total_skipped_paused += 1);
+                    }
+                    last_clipboard_content = current_content;
+                    continue;
+                }
+
+                if clipboard_event {
+                    if current_content.trim().is_empty() {
+                        record_metric(&app_clone, |m| m.total_skipped_empty += 1);
+                        last_clipboard_content = current_content;
+                        continue;
+                    }
+
+                    // Skip content we wrote ourselves
+                    let should_ignore = self_writes_clone
+                        .lock()
+                        .map(|mut self_writes_guard| self_writes_guard.take(&current_content))
+                        .unwrap_or(false);
+                    
+                    if should_ignore {
+                        record_metric(&app_clone, |m| m.total_skipped_self_write += 1);
+                        last_clipboard_content = current_content;
+                        continue;
+                    }
+
+                    match add_item_to_history(
+                        &app_clone,
+                        &current_content,
+                        platform::frontmost_app(),
+                        platform::active_display(),
+                    ) {
+                        Ok(Some(item)) => {
+                            println!("Added clipboard item: {}", item.content.chars().take(50).collect::<String>());
+
+                            // Emit event to frontend to refresh history
+                            emit_clipboard_updated(&app_clone, item);
+                        }
+                        Ok(None) => {}
+                        Err(e) => eprintln!("Failed to add clipboard item: {}", e),
+                    }
+                    
+                    last_clipboard_content = current_content;
+                }
+            }
+        }
+    });
+    
+    Ok(())
+}
+
+// Tauri command to copy text to clipboard without triggering monitoring.
+// Empty content is refused: it's what hash-only items carry, and copying it
+// would silently wipe the clipboard (use copy_history_item for items).
+#[tauri::command]
+async fn copy_to_clipboard(
+    app: AppHandle,
+    content: String,
+    self_writes: State<'_, SelfWrites>,
+    history: State<'_, ClipboardHistory>
+) -> Result<(), String> {
+    if content.is_empty() {
+        return Err("Refusing to copy empty content to the clipboard".to_string());
+    }
+    // Attribute the copy to the newest history item with the same content, if any
+    let id = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard
+            .iter()
+            .find(|item| !item.hash_only && item.content == content)
+            .map(|item| item.id)
+    };
+    write_to_clipboard(&app, &self_writes, content)?;
+    set_last_copied(&app, id)
+}
+
+// Tauri command to copy a history item to the clipboard by id
+#[tauri::command]
+async fn copy_history_item(
+    app: AppHandle,
+    id: u64,
+    history: State<'_, ClipboardHistory>,
+    self_writes: State<'_, SelfWrites>
+) -> Result<(), String> {
+    let content = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let item = history_guard
+            .iter()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("No history item with id {}", id))?;
+        if item.hash_only {
+            return Err(format!("Item {} only has its hash stored and can't be copied", id));
+        }
+        item.content.clone()
+    };
+    write_to_clipboard(&app, &self_writes, content)?;
+    set_last_copied(&app, Some(id))
+}
+
+// Payload of the `sequence-step` event, sent after each item of a sequence
+// has been written to the clipboard
+#[derive(Debug, Clone, Serialize)]
+pub struct SequenceStep {
+    pub id: u64,
+    // Zero-based position in the sequence
+    pub index: usize,
+    pub total: usize,
+}
+
+// Tauri command to copy several history items one after another, waiting
+// `delay_ms` between them so each can be pasted separately. Runs in the
+// background; progress is reported through `sequence-step` events.
+#[tauri::command]
+async fn copy_sequence(
+    app: AppHandle,
+    ids: Vec<u64>,
+    delay_ms: u64,
+    history: State<'_, ClipboardHistory>,
+    self_writes: State<'_, SelfWrites>
+) -> Result<(), String> {
+    // Resolve everything up front so a bad id fails before anything is copied
+    let contents: Vec<(u64, String)> = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        ids.iter()
+            .map(|id| {
+                let item = history_guard
+                    .iter()
+                    .find(|item| item.id == *id)
+                    .ok_or_else(|| format!("No history item with id {}", id))?;
+                if item.hash_only {
+                    return Err(format!("Item {} only has its hash stored and can't be copied", id));
+                }
+                Ok((item.id, item.content.clone()))
+            })
+            .collect::<Result<_, String>>()?
+    };
+
+    let self_writes = self_writes.inner().clone();
+    let delay = Duration::from_millis(delay_ms);
+    thread::spawn(move || {
+        let total = contents.len();
+        for (index, (id, content)) in contents.into_iter().enumerate() {
+            if index > 0 {
+                thread::sleep(delay);
+            }
+            if let Err(e) = write_to_clipboard(&app, &self_writes, content) {
+                eprintln!("Copy sequence stopped at step {}: {}", index, e);
+                return;
+            }
+            if let Err(e) = set_last_copied(&app, Some(id)) {
+                eprintln!("Failed to record last copied item: {}", e);
+            }
+            if let Err(e) = app.emit("sequence-step", SequenceStep { id, index, total }) {
+                eprintln!("Failed to emit sequence step event: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+// Tauri command to get the platform clipboard change counter, where there is one
+#[tauri::command]
+async fn get_clipboard_change_count() -> Result<Option<u64>, String> {
+    Ok(platform::clipboard_change_count())
+}
+
+// Tauri command to get the id of the item CopyMate last copied, if any
+#[tauri::command]
+async fn get_last_copied_id(last_copied: State<'_, LastCopied>) -> Result<Option<u64>, String> {
+    let last_copied_guard = last_copied.lock().map_err(|e| format!("Failed to lock last copied id: {}", e))?;
+    Ok(*last_copied_guard)
+}
+
+// Helper to record the last copied item, emitting `last-copied-changed` when it changes
+fn set_last_copied(app: &AppHandle, id: Option<u64>) -> Result<(), String> {
+    let changed = {
+        let last_copied = app.state::<LastCopied>();
+        let mut last_copied_guard = last_copied.lock().map_err(|e| format!("Failed to lock last copied id: {}", e))?;
+        let changed = *last_copied_guard != id;
+        *last_copied_guard = id;
+        changed
+    };
+    if changed {
+        if let Err(e) = app.emit("last-copied-changed", id) {
+            eprintln!("Failed to emit last copied event: {}", e);
+        }
+    }
+    Ok(())
+}
+
+// Tauri command to set how long CopyMate's own clipboard writes are remembered
+#[tauri::command]
+async fn set_self_write_expiry(
+    app: AppHandle,
+    seconds: u64,
+    settings: State<'_, AppSettings>
+) -> Result<(), String> {
+    if seconds == 0 {
+        return Err("Self-write expiry must be at least 1 second".to_string());
+    }
+    {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.self_write_expiry_secs = Some(seconds);
+    }
+    persist_settings(&app);
+    Ok(())
+}
+
+// Tauri command to count self-writes the monitor hasn't seen come back yet
+#[tauri::command]
+async fn get_pending_self_writes(self_writes: State<'_, SelfWrites>) -> Result<usize, String> {
+    let self_writes_guard = self_writes.lock().map_err(|e| format!("Failed to lock self-writes: {}", e))?;
+    Ok(self_writes_guard.pending_count())
+}
+
+// Helper to write to the clipboard without the monitor capturing it
+fn write_to_clipboard(app: &AppHandle, self_writes: &SelfWrites, content: String) -> Result<(), String> {
+    // Remember the write so the monitor skips it when it sees the change
+    {
+        let mut self_writes_guard = self_writes.lock().map_err(|e| format!("Failed to lock self-writes: {}", e))?;
+        self_writes_guard.record(&content);
+    }
+    
+    // Copy to clipboard
+    app.clipboard().write_text(content)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+    
+    Ok(())
+}
+
+// Tauri command to move the history item matching the live clipboard to the
+// front and mark it used, returning it (None if nothing matches)
+#[tauri::command]
+async fn promote_current(app: AppHandle, history: State<'_, ClipboardHistory>) -> Result<Option<ClipboardItem>, String> {
+    let current = app.clipboard().read_text().map_err(|e| format!("Failed to read clipboard: {}", e))?;
+    let current_hash = hash_content(&current);
+    let current_keyed_hash = {
+        let hash_key = app.state::<HashKey>();
+        let hash_key_guard = hash_key.lock().map_err(|e| format!("Failed to lock hash key: {}", e))?;
+        keyed_hash(&hash_key_guard, &current)
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Time error: {}", e))?
+        .as_secs();
+
+    let promoted = {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        // Items saved before hashes were recorded are matched on content
+        let Some(position) = history_guard.iter().position(|item| {
+            if item.content_hash.is_empty() {
+                item.content == current
+            } else if item.hash_only {
+                item.content_hash == current_keyed_hash
+            } else {
+                item.content_hash == current_hash
+            }
+        }) else {
+            return Ok(None);
+        };
+        let Some(mut item) = history_guard.remove(position) else {
+            return Ok(None);
+        };
+        item.last_used = Some(now);
+        history_guard.push_front(item.clone());
+        item
+    };
+    persist_history(&app);
+    Ok(Some(promoted))
+}
+
+// Tauri command to get the item id assigned to each quick slot, slot 1 first
+#[tauri::command]
+async fn get_slots(slots: State<'_, QuickSlots>) -> Result<[Option<u64>; 9], String> {
+    let slots_guard = slots.lock().map_err(|e| format!("Failed to lock quick slots: {}", e))?;
+    Ok(*slots_guard)
+}
+
+// Tauri command to assign a history item to quick slot 1-9
+#[tauri::command]
+async fn assign_slot(
+    app: AppHandle,
+    slot: u8,
+    id: u64,
+    history: State<'_, ClipboardHistory>,
+    slots: State<'_, QuickSlots>
+) -> Result<(), String> {
+    let index = slot_index(slot)?;
+    {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        if !history_guard.iter().any(|item| item.id == id) {
+            return Err(format!("No history item with id {}", id));
+        }
+    }
+    {
+        let mut slots_guard = slots.lock().map_err(|e| format!("Failed to lock quick slots: {}", e))?;
+        slots_guard[index] = Some(id);
+    }
+    persist_slots(&app);
+    Ok(())
+}
+
+// Tauri command to empty a quick slot
+#[tauri::command]
+async fn clear_slot(app: AppHandle, slot: u8, slots: State<'_, QuickSlots>) -> Result<(), String> {
+    let index = slot_index(slot)?;
+    {
+        let mut slots_guard = slots.lock().map_err(|e| format!("Failed to lock quick slots: {}", e))?;
+        slots_guard[index] = None;
+    }
+    persist_slots(&app);
+    Ok(())
+}
+
+// Tauri command to copy the item in a quick slot to the clipboard
+#[tauri::command]
+async fn copy_slot(
+    app: AppHandle,
+    slot: u8,
+    slots: State<'_, QuickSlots>,
+    history: State<'_, ClipboardHistory>,
+    self_writes: State<'_, SelfWrites>
+) -> Result<(), String> {
+    let index = slot_index(slot)?;
+    let id = {
+        let slots_guard = slots.lock().map_err(|e| format!("Failed to lock quick slots: {}", e))?;
+        slots_guard[index].ok_or_else(|| format!("Slot {} is empty", slot))?
+    };
+    let content = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let item = history_guard
+            .iter()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("Item {} in slot {} is no longer in history", id, slot))?;
+        if item.hash_only {
+            return Err(format!("Item {} only has its hash stored and can't be copied", id));
+        }
+        item.content.clone()
+    };
+    write_to_clipboard(&app, &self_writes, content)?;
+    set_last_copied(&app, Some(id))
+}
+
+// Helper to turn a 1-based slot number into an array index
+fn slot_index(slot: u8) -> Result<usize, String> {
+    match slot {
+        1..=9 => Ok(usize::from(slot - 1)),
+        _ => Err(format!("Slot must be between 1 and 9, got {}", slot)),
+    }
+}
+
+// Tauri command to clear clipboard history
+#[tauri::command]
+async fn clear_clipboard_history(app: AppHandle, history: State<'_, ClipboardHistory>) -> Result<(), String> {
+    {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard.clear();
+        if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+            index_guard.rebuild(std::iter::empty());
+        }
+    }
+    persist_history(&app);
+    Ok(())
+}
+
+// Tauri command to list workspace names, the active one first
+#[tauri::command]
+async fn list_workspaces(workspaces: State<'_, Workspaces>) -> Result<Vec<String>, String> {
+    let workspaces_guard = workspaces.lock().map_err(|e| format!("Failed to lock workspaces: {}", e))?;
+    let mut names: Vec<String> = workspaces_guard.inactive.keys().cloned().collect();
+    names.sort();
+    names.insert(0, workspaces_guard.active.clone());
+    Ok(names)
+}
+
+// Tauri command to make another workspace active, creating it if needed. The
+// current history is parked under the previous workspace's name.
+#[tauri::command]
+async fn switch_workspace(
+    app: AppHandle,
+    name: String,
+    workspaces: State<'_, Workspaces>,
+    history: State<'_, ClipboardHistory>
+) -> Result<(), String> {
+    let name = validate_workspace_name(&name)?;
+    {
+        let mut workspaces_guard = workspaces.lock().map_err(|e| format!("Failed to lock workspaces: {}", e))?;
+        if workspaces_guard.active == name {
+            return Ok(());
+        }
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let incoming = workspaces_guard.inactive.remove(&name).unwrap_or_default();
+        let outgoing = std::mem::replace(&mut *history_guard, incoming);
+        let previous = std::mem::replace(&mut workspaces_guard.active, name);
+        workspaces_guard.inactive.insert(previous, outgoing);
+        if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+            index_guard.rebuild(history_guard.iter());
+        }
+    }
+    persist_workspaces(&app);
+    persist_history(&app);
+    Ok(())
+}
+
+// Source app recorded for imported items
+const IMPORT_SOURCE: &str = "import";
+// Longest line imported; longer ones are counted as errors and skipped
+const MAX_IMPORT_LINE_BYTES: usize = 1024 * 1024;
+
+// Outcome of importing a file
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    // Blank lines, duplicates and lines rejected by capture filters
+    pub skipped: usize,
+    // Items dropped by the MAX_HISTORY_ITEMS cap during the import, imported
+    // or already there. Non-zero means history couldn't hold the whole file.
+    pub trimmed: usize,
+    // Lines that weren't UTF-8, were over MAX_IMPORT_LINE_BYTES or failed to
+    // capture, plus one if reading stopped early on an I/O error
+    pub errored: usize,
+}
+
+// Tauri command to import a plain text file, one item per line. Lines go
+// through the capture filters and dedup, but not append mode, PII events, the
+// audit log or the webhook. The file is read line by line so large files
+// aren't loaded into memory at once.
+#[tauri::command]
+async fn import_lines(app: AppHandle, path: String) -> Result<ImportReport, String> {
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let history = app.state::<ClipboardHistory>();
+    let mut ids: HashSet<u64> = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard.iter().map(|item| item.id).collect()
+    };
+
+    // Bad lines are counted rather than aborting, since earlier lines are
+    // already in history and the report is the only record of them
+    let mut report = ImportReport::default();
+    let mut reader = BufReader::new(file);
+    let mut buf = Vec::new();
+    loop {
+        let line = match read_import_line(&mut reader, &mut buf) {
+            Ok(Some(true)) => String::from_utf8(std::mem::take(&mut buf)),
+            Ok(Some(false)) => {
+                report.errored += 1;
+                continue;
+            }
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", path, e);
+                report.errored += 1;
+                break;
+            }
+        };
+        let Ok(line) = line else {
+            report.errored += 1;
+            continue;
+        };
+        if line.trim().is_empty() {
+            report.skipped += 1;
+            continue;
+        }
+        match capture_item(&app, &line, Some(IMPORT_SOURCE.to_string()), None, CaptureMode::Import) {
+            Ok(Some(item)) => {
+                ids.insert(item.id);
+                report.imported += 1;
+            }
+            Ok(None) => report.skipped += 1,
+            Err(e) => {
+                eprintln!("Failed to import line: {}", e);
+                report.errored += 1;
+            }
+        }
+    }
+
+    let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+    let remaining = history_guard.iter().filter(|item| ids.contains(&item.id)).count();
+    report.trimmed = ids.len() - remaining;
+    Ok(report)
+}
+
+// Helper to read one line into `buf` without its terminator. Returns None at
+// the end of the file, and Some(false) for a line over MAX_IMPORT_LINE_BYTES,
+// the rest of which is skipped.
+fn read_import_line(reader: &mut impl BufRead, buf: &mut Vec<u8>) -> std::io::Result<Option<bool>> {
+    buf.clear();
+    if (&mut *reader).take(MAX_IMPORT_LINE_BYTES as u64 + 1).read_until(b'\n', buf)? == 0 {
+        return Ok(None);
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+        return Ok(Some(true));
+    }
+    if buf.len() <= MAX_IMPORT_LINE_BYTES {
+        return Ok(Some(true));
+    }
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            break;
+        }
+        match available.iter().position(|&b| b == b'\n') {
+            Some(end) => {
+                reader.consume(end + 1);
+                break;
+            }
+            None => {
+                let len = available.len();
+                reader.consume(len);
+            }
+        }
+    }
+    Ok(Some(false))
+}
+
+// Tauri command to write the current history to a backup file, returning the item count
+#[tauri::command]
+async fn export_backup(path: String, history: State<'_, ClipboardHistory>) -> Result<usize, String> {
+    let items: Vec<ClipboardItem> = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard.iter().cloned().collect()
+    };
+    storage::write_json(&PathBuf::from(path), &items)?;
+    Ok(items.len())
+}
+
+// Tauri command to load a backup file, returning the item count. With a
+// `workspace` other than the active one the backup replaces that workspace
+// (created if needed) and the current history is left alone.
+#[tauri::command]
+async fn restore_backup(
+    app: AppHandle,
+    path: String,
+    workspace: Option<String>,
+    settings: State<'_, AppSettings>,
+    workspaces: State<'_, Workspaces>,
+    history: State<'_, ClipboardHistory>
+) -> Result<usize, String> {
+    let path = PathBuf::from(path);
+    let mut items: VecDeque<ClipboardItem> = storage::read_json::<Vec<ClipboardItem>>(&path)?
+        .ok_or_else(|| format!("Backup {} does not exist", path.display()))?
+        .into();
+    backfill_cids(&mut items);
+    {
+        let settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        refresh_previews(&mut items, &settings_guard);
+    }
+    let count = items.len();
+    let target = workspace.as_deref().map(validate_workspace_name).transpose()?;
+
+    let restored_to_active = {
+        let mut workspaces_guard = workspaces.lock().map_err(|e| format!("Failed to lock workspaces: {}", e))?;
+        match target {
+            Some(name) if name != workspaces_guard.active => {
+                workspaces_guard.inactive.insert(name, items);
+                false
+            }
+            _ => {
+                let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+                *history_guard = items;
+                if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+                    index_guard.rebuild(history_guard.iter());
+                }
+                true
+            }
+        }
+    };
+    if restored_to_active {
+        persist_history(&app);
+    } else {
+        persist_workspaces(&app);
+    }
+    Ok(count)
+}
+
+// Tauri command to give every item with this content hash, in any workspace,
+// the union of their tags and metadata. Where metadata keys conflict, the
+// active workspace's newest value wins. Returns how many items changed.
+#[tauri::command]
+async fn consolidate_across_workspaces(
+    app: AppHandle,
+    content_hash: String,
+    workspaces: State<'_, Workspaces>,
+    history: State<'_, ClipboardHistory>
+) -> Result<usize, String> {
+    let (active_updated, inactive_updated) = {
+        let mut workspaces_guard = workspaces.lock().map_err(|e| format!("Failed to lock workspaces: {}", e))?;
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        // Items saved before hashes were recorded have theirs computed here
+        let matches = |item: &ClipboardItem| {
+            if item.content_hash.is_empty() {
+                hash_content(&item.content) == content_hash
+            } else {
+                item.content_hash == content_hash
+            }
+        };
+
+        // Active workspace first so its values take precedence
+        let mut tags: Vec<String> = Vec::new();
+        let mut metadata: HashMap<String, String> = HashMap::new();
+        let inactive_items = workspaces_guard.inactive.values().flat_map(|items| items.iter());
+        for item in history_guard.iter().chain(inactive_items).filter(|item| matches(item)) {
+            for tag in &item.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            for (key, value) in &item.metadata {
+                metadata.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+
+        let apply = |item: &mut ClipboardItem| {
+            if !matches(item) || (item.tags == tags && item.metadata == metadata) {
+                return false;
+            }
+            item.tags = tags.clone();
+            item.metadata = metadata.clone();
+            true
+        };
+        let active_updated = history_guard.iter_mut().map(apply).filter(|updated| *updated).count();
+        let inactive_updated = workspaces_guard
+            .inactive
+            .values_mut()
+            .flat_map(|items| items.iter_mut())
+            .map(apply)
+            .filter(|updated| *updated)
+            .count();
+        (active_updated, inactive_updated)
+    };
+    if active_updated > 0 {
+        persist_history(&app);
+    }
+    if inactive_updated > 0 {
+        persist_workspaces(&app);
+    }
+    Ok(active_updated + inactive_updated)
+}
+
+fn validate_workspace_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Workspace name must not be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+// Format version of state bundles. Bundles of any other version are refused.
+const BUNDLE_VERSION: u32 = 1;
+
+// Full app state, for moving CopyMate to another machine. Pins and tags travel
+// on the items themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateBundle {
+    pub version: u32,
+    pub settings: Settings,
+    pub active_workspace: String,
+    // Items of every workspace, the active one included
+    pub workspaces: HashMap<String, Vec<ClipboardItem>>,
+    pub pin_order: Vec<u64>,
+    pub slots: [Option<u64>; 9],
+    pub saved_searches: Vec<SavedSearch>,
+    #[serde(default)]
+    pub auto_tag_rules: Vec<AutoTagRule>,
+    // User-defined redaction rulesets as patterns, by name
+    #[serde(default)]
+    pub redaction_rulesets: HashMap<String, Vec<String>>,
+}
+
+// Just the version, read first so other versions get a clear error instead of
+// a parse failure
+#[derive(Deserialize)]
+struct BundleHeader {
+    version: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleImportMode {
+    // Add the bundle to the current state, keeping the current settings
+    Merge,
+    // Drop the current state in favour of the bundle
+    Replace,
+}
+
+// What import_bundle brought in
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BundleImportReport {
+    pub version: u32,
+    pub settings_imported: bool,
+    pub workspaces: usize,
+    pub items: usize,
+    // Bundle items whose content was already in the workspace (merge only)
+    pub duplicates: usize,
+    pub pinned: usize,
+    pub slots: usize,
+    pub saved_searches: usize,
+    pub auto_tag_rules: usize,
+    pub redaction_rulesets: usize,
+    // Oldest unpinned items dropped to keep workspaces within MAX_HISTORY_ITEMS
+    pub trimmed: usize,
+}
+
+// Tauri command to write settings, every workspace, the pin order, quick slots,
+// saved searches, auto-tag rules and redaction rulesets to one bundle file.
+// Returns the number of items written.
+#[tauri::command]
+async fn export_bundle(app: AppHandle, path: String) -> Result<usize, String> {
+    let settings = app.state::<AppSettings>();
+    let workspaces = app.state::<Workspaces>();
+    let history = app.state::<ClipboardHistory>();
+    let pin_order = app.state::<PinOrder>();
+    let slots = app.state::<QuickSlots>();
+    let searches = app.state::<SavedSearches>();
+    let rules = app.state::<AutoTagRules>();
+    let rulesets = app.state::<RedactionRulesets>();
+    let bundle = {
+        let settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        let workspaces_guard = workspaces.lock().map_err(|e| format!("Failed to lock workspaces: {}", e))?;
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let pin_order_guard = pin_order.lock().map_err(|e| format!("Failed to lock pin order: {}", e))?;
+        let slots_guard = slots.lock().map_err(|e| format!("Failed to lock quick slots: {}", e))?;
+        let searches_guard = searches.lock().map_err(|e| format!("Failed to lock saved searches: {}", e))?;
+        let rules_guard = rules.lock().map_err(|e| format!("Failed to lock auto-tag rules: {}", e))?;
+        let rulesets_guard = rulesets.lock().map_err(|e| format!("Failed to lock redaction rulesets: {}", e))?;
+
+        let mut all_workspaces: HashMap<String, Vec<ClipboardItem>> = workspaces_guard
+            .inactive
+            .iter()
+            .map(|(name, items)| (name.clone(), items.iter().cloned().collect()))
+            .collect();
+        all_workspaces.insert(workspaces_guard.active.clone(), history_guard.iter().cloned().collect());
+        StateBundle {
+            version: BUNDLE_VERSION,
+            settings: settings_guard.clone(),
+            active_workspace: workspaces_guard.active.clone(),
+            workspaces: all_workspaces,
+            pin_order: pin_order_guard.clone(),
+            slots: *slots_guard,
+            saved_searches: searches_guard.clone(),
+            auto_tag_rules: rules_guard.iter().map(|(rule, _)| rule.clone()).collect(),
+            redaction_rulesets: rulesets_guard
+                .iter()
+                .map(|(name, (patterns, _))| (name.clone(), patterns.clone()))
+                .collect(),
+        }
+    };
+    storage::write_json(&PathBuf::from(path), &bundle)?;
+    Ok(bundle.workspaces.values().map(Vec::len).sum())
+}
+
+// Helper to merge imported items into a workspace. Content that's already there
+// keeps its item, which gains the imported pin and tags; new items get an id
+// not taken in the workspace. Returns the id each imported id ended up as.
+fn merge_items(
+    target: &mut VecDeque<ClipboardItem>,
+    items: VecDeque<ClipboardItem>,
+    report: &mut BundleImportReport
+) -> HashMap<u64, u64> {
+    let mut ids = HashMap::new();
+    for mut item in items {
+        if item.pinned {
+            report.pinned += 1;
+        }
+        if let Some(existing) = target.iter_mut().find(|existing| existing.cid == item.cid) {
+            ids.insert(item.id, existing.id);
+            existing.pinned |= item.pinned;
+            for tag in item.tags {
+                if !existing.tags.contains(&tag) {
+                    existing.tags.push(tag);
+                }
+            }
+            report.duplicates += 1;
+            continue;
+        }
+        let imported_id = item.id;
+        while target.iter().any(|existing| existing.id == item.id) {
+            item.id += 1;
+        }
+        ids.insert(imported_id, item.id);
+        target.push_back(item);
+        report.items += 1;
+    }
+    target.make_contiguous().sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+    report.trimmed += trim_to_capacity(target);
+    ids
 }
 
-// Application state to store clipboard history
-pub type ClipboardHistory = Arc<Mutex<VecDeque<ClipboardItem>>>;
+// Helper to drop the oldest unpinned items until at most MAX_HISTORY_ITEMS
+// remain, as captures do one at a time. Returns how many were dropped.
+fn trim_to_capacity(items: &mut VecDeque<ClipboardItem>) -> usize {
+    let mut trimmed = 0;
+    while items.len() > MAX_HISTORY_ITEMS {
+        let Some(oldest_unpinned) = items.iter().rposition(|item| !item.pinned) else {
+            break;
+        };
+        items.remove(oldest_unpinned);
+        trimmed += 1;
+    }
+    trimmed
+}
+
+// Tauri command to restore a bundle written by export_bundle. `replace` swaps
+// the whole state for the bundle's. `merge` keeps the current settings, adds
+// the bundle's items to workspaces of the same name, fills empty quick slots,
+// and adds auto-tag rules, saved searches and rulesets that aren't there yet.
+#[tauri::command]
+async fn import_bundle(
+    app: AppHandle,
+    path: String,
+    mode: BundleImportMode
+) -> Result<BundleImportReport, String> {
+    let path = PathBuf::from(path);
+    let header = storage::read_json::<BundleHeader>(&path)?
+        .ok_or_else(|| format!("Bundle {} does not exist", path.display()))?;
+    if header.version != BUNDLE_VERSION {
+        return Err(format!(
+            "Unsupported bundle version {} (this version of CopyMate reads version {})",
+            header.version, BUNDLE_VERSION
+        ));
+    }
+    let bundle = storage::read_json::<StateBundle>(&path)?
+        .ok_or_else(|| format!("Bundle {} does not exist", path.display()))?;
 
-// Global flag to track when we're programmatically setting clipboard
-pub type IgnoreNextClipboard = Arc<Mutex<bool>>;
+    let bundle_active = validate_workspace_name(&bundle.active_workspace)?;
+    let mut incoming: HashMap<String, VecDeque<ClipboardItem>> = HashMap::new();
+    for (name, items) in bundle.workspaces {
+        let mut items: VecDeque<ClipboardItem> = items.into();
+        backfill_cids(&mut items);
+        incoming.entry(validate_workspace_name(&name)?).or_default().extend(items);
+    }
+    if let BundleImportMode::Replace = mode {
+        validate_settings(&bundle.settings)?;
+    }
+    let imported_rules = compile_auto_tag_rules(bundle.auto_tag_rules)?;
+    let imported_rulesets = compile_redaction_rulesets(bundle.redaction_rulesets)?;
 
-// Tauri command to get current clipboard content
+    let mut report = BundleImportReport {
+        version: header.version,
+        workspaces: incoming.len(),
+        ..Default::default()
+    };
+    let settings = app.state::<AppSettings>();
+    let workspaces = app.state::<Workspaces>();
+    let history = app.state::<ClipboardHistory>();
+    let pin_order = app.state::<PinOrder>();
+    let slots = app.state::<QuickSlots>();
+    let searches = app.state::<SavedSearches>();
+    let rules = app.state::<AutoTagRules>();
+    let rulesets = app.state::<RedactionRulesets>();
+    let ingest_changed = {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        let mut workspaces_guard = workspaces.lock().map_err(|e| format!("Failed to lock workspaces: {}", e))?;
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let mut pin_order_guard = pin_order.lock().map_err(|e| format!("Failed to lock pin order: {}", e))?;
+        let mut slots_guard = slots.lock().map_err(|e| format!("Failed to lock quick slots: {}", e))?;
+        let mut searches_guard = searches.lock().map_err(|e| format!("Failed to lock saved searches: {}", e))?;
+        let mut rules_guard = rules.lock().map_err(|e| format!("Failed to lock auto-tag rules: {}", e))?;
+        let mut rulesets_guard = rulesets.lock().map_err(|e| format!("Failed to lock redaction rulesets: {}", e))?;
+
+        let ingest_changed = match mode {
+            BundleImportMode::Replace => {
+                let ingest_changed = ingest_settings_changed(&settings_guard, &bundle.settings);
+                *settings_guard = bundle.settings;
+                report.settings_imported = true;
+
+                let items = incoming.values().flatten();
+                report.items = items.clone().count();
+                report.pinned = items.filter(|item| item.pinned).count();
+                report.trimmed = incoming.values_mut().map(trim_to_capacity).sum();
+                *history_guard = incoming.remove(&bundle_active).unwrap_or_default();
+                workspaces_guard.active = bundle_active;
+                workspaces_guard.inactive = incoming;
+
+                *pin_order_guard = bundle.pin_order;
+                *slots_guard = bundle.slots;
+                report.slots = slots_guard.iter().flatten().count();
+                report.saved_searches = bundle.saved_searches.len();
+                *searches_guard = bundle.saved_searches;
+                report.auto_tag_rules = imported_rules.len();
+                *rules_guard = imported_rules;
+                report.redaction_rulesets = imported_rulesets.len();
+                *rulesets_guard = imported_rulesets;
+                ingest_changed
+            }
+            BundleImportMode::Merge => {
+                // Pin order and slots refer to items of the bundle's active
+                // workspace, so they only carry over when that's active here too
+                let mut active_ids = HashMap::new();
+                for (name, items) in incoming {
+                    if name == workspaces_guard.active {
+                        let ids = merge_items(&mut history_guard, items, &mut report);
+                        if name == bundle_active {
+                            active_ids = ids;
+                        }
+                    } else {
+                        merge_items(workspaces_guard.inactive.entry(name).or_default(), items, &mut report);
+                    }
+                }
+                // Items the cap pushed out can't be pinned in order or slotted
+                active_ids.retain(|_, id| history_guard.iter().any(|item| item.id == *id));
+
+                for id in bundle.pin_order.iter().filter_map(|id| active_ids.get(id)) {
+                    if !pin_order_guard.contains(id) {
+                        pin_order_guard.push(*id);
+                    }
+                }
+                for (slot, imported) in slots_guard.iter_mut().zip(bundle.slots) {
+                    if slot.is_none() {
+                        if let Some(id) = imported.and_then(|id| active_ids.get(&id)) {
+                            *slot = Some(*id);
+                            report.slots += 1;
+                        }
+                    }
+                }
+                for search in bundle.saved_searches {
+                    if !searches_guard.iter().any(|existing| existing.name == search.name) {
+                        searches_guard.push(search);
+                        report.saved_searches += 1;
+                    }
+                }
+                for (rule, regex) in imported_rules {
+                    let exists = rules_guard
+                        .iter()
+                        .any(|(existing, _)| existing.pattern == rule.pattern && existing.tag == rule.tag);
+                    if !exists {
+                        rules_guard.push((rule, regex));
+                        report.auto_tag_rules += 1;
+                    }
+                }
+                for (name, ruleset) in imported_rulesets {
+                    if let Entry::Vacant(entry) = rulesets_guard.entry(name) {
+                        entry.insert(ruleset);
+                        report.redaction_rulesets += 1;
+                    }
+                }
+                false
+            }
+        };
+
+        refresh_previews(workspaces_guard.inactive.values_mut().flatten(), &settings_guard);
+        refresh_previews(history_guard.iter_mut(), &settings_guard);
+        if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+            index_guard.rebuild(history_guard.iter());
+        }
+        ingest_changed
+    };
+
+    refresh_capture_filters(&app)?;
+    persist_settings(&app);
+    persist_workspaces(&app);
+    persist_history(&app);
+    persist_slots(&app);
+    persist_pin_order(&app);
+    persist_saved_searches(&app);
+    persist_auto_tag_rules(&app);
+    persist_redaction_rulesets(&app);
+    if ingest_changed {
+        sync_ingest_server(&app)?;
+    }
+    Ok(report)
+}
+
+// Tauri command to remove every item not in `ids`, returning how many were removed.
+// Pinned items are kept unless `force` is set.
 #[tauri::command]
-fn get_clipboard_text(app: tauri::AppHandle) -> Result<String, String> {
-    match app.clipboard().read_text() {
-        Ok(text) => Ok(text),
-        Err(e) => Err(format!("Failed to read clipboard: {}", e)),
+async fn keep_only(
+    app: AppHandle,
+    ids: Vec<u64>,
+    force: Option<bool>,
+    history: State<'_, ClipboardHistory>
+) -> Result<usize, String> {
+    let force = force.unwrap_or(false);
+    let keep: HashSet<u64> = ids.into_iter().collect();
+    let removed = {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let before = history_guard.len();
+        let mut removed_ids = Vec::new();
+        history_guard.retain(|item| {
+            let kept = keep.contains(&item.id) || (item.pinned && !force);
+            if !kept {
+                removed_ids.push(item.id);
+            }
+            kept
+        });
+        if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+            for id in removed_ids {
+                index_guard.remove(id);
+            }
+        }
+        before - history_guard.len()
+    };
+    if removed > 0 {
+        persist_history(&app);
     }
+    Ok(removed)
 }
 
-// Tauri command to get clipboard history
+// Tauri command to keep only the `k` newest items of each tag. An item is
+// removed only if no tag keeps it and it isn't pinned; untagged items are left
+// alone. Returns the count removed.
+#[tauri::command]
+async fn keep_top_per_tag(app: AppHandle, k: usize, history: State<'_, ClipboardHistory>) -> Result<usize, String> {
+    let removed = {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        let mut by_tag: HashMap<&str, Vec<&ClipboardItem>> = HashMap::new();
+        for item in history_guard.iter() {
+            for tag in &item.tags {
+                by_tag.entry(tag.as_str()).or_default().push(item);
+            }
+        }
+        let mut kept: HashSet<u64> = HashSet::new();
+        for items in by_tag.values_mut() {
+            // Stable sort, so same-second items keep their history order
+            items.sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+            kept.extend(items.iter().take(k).map(|item| item.id));
+        }
+
+        let mut removed_ids = Vec::new();
+        history_guard.retain(|item| {
+            let keep = item.tags.is_empty() || item.pinned || kept.contains(&item.id);
+            if !keep {
+                removed_ids.push(item.id);
+            }
+            keep
+        });
+        if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+            for id in &removed_ids {
+                index_guard.remove(*id);
+            }
+        }
+        removed_ids.len()
+    };
+    if removed > 0 {
+        persist_history(&app);
+    }
+    Ok(removed)
+}
+
+// Longest gap between two copies for them to count as one progressive selection
+const PREFIX_CHAIN_WINDOW_SECS: u64 = 60;
+
+// Tauri command to collapse runs of progressively longer copies ("foo",
+// "foo bar", "foo bar baz"). An item is removed when the next newer item,
+// copied within PREFIX_CHAIN_WINDOW_SECS, is longer and starts or ends with
+// it. Pinned, tagged and annotated items are never removed. Returns the count removed.
+#[tauri::command]
+async fn merge_prefix_chains(app: AppHandle, history: State<'_, ClipboardHistory>) -> Result<usize, String> {
+    let removed = {
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        // History is newest first, so each item is compared with the one before it
+        let superseded: HashSet<u64> = history_guard
+            .iter()
+            .zip(history_guard.iter().skip(1))
+            .filter(|(newer, item)| {
+                !item.pinned
+                    && !item.hash_only
+                    && item.tags.is_empty()
+                    && item.metadata.is_empty()
+                    && !item.content.is_empty()
+                    && !newer.hash_only
+                    && newer.timestamp.saturating_sub(item.timestamp) <= PREFIX_CHAIN_WINDOW_SECS
+                    && newer.content.len() > item.content.len()
+                    && (newer.content.starts_with(&item.content) || newer.content.ends_with(&item.content))
+            })
+            .map(|(_, item)| item.id)
+            .collect();
+        history_guard.retain(|item| !superseded.contains(&item.id));
+        if let Ok(mut index_guard) = app.state::<HistoryIndex>().lock() {
+            for id in &superseded {
+                index_guard.remove(*id);
+            }
+        }
+        superseded.len()
+    };
+    if removed > 0 {
+        persist_history(&app);
+    }
+    Ok(removed)
+}
+
+// Tauri command to get the capture counters for this session
+#[tauri::command]
+async fn get_capture_metrics(metrics: State<'_, Metrics>) -> Result<CaptureMetrics, String> {
+    let metrics_guard = metrics.lock().map_err(|e| format!("Failed to lock capture metrics: {}", e))?;
+    Ok(metrics_guard.clone())
+}
+
+// Tauri command to get the rolling average clipboard read time in milliseconds
+#[tauri::command]
+async fn get_read_latency_ms(read_latency: State<'_, ReadLatency>) -> Result<f64, String> {
+    let latency_guard = read_latency.lock().map_err(|e| format!("Failed to lock read latency: {}", e))?;
+    Ok(latency_guard.average_ms())
+}
+
+// Tauri command to snapshot the ids currently in history
+#[tauri::command]
+async fn checkpoint(
+    history: State<'_, ClipboardHistory>,
+    checkpoint: State<'_, HistoryCheckpoint>
+) -> Result<(), String> {
+    let ids = {
+        let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        history_guard.iter().map(|item| item.id).collect()
+    };
+    let mut checkpoint_guard = checkpoint.lock().map_err(|e| format!("Failed to lock checkpoint: {}", e))?;
+    *checkpoint_guard = Some(ids);
+    Ok(())
+}
+
+// Tauri command to get what was added/removed since the last checkpoint.
+// Without a checkpoint every item counts as added.
 #[tauri::command]
-async fn get_clipboard_history(history: State<'_, ClipboardHistory>) -> Result<Vec<ClipboardItem>, String> {
+async fn diff_since_checkpoint(
+    history: State<'_, ClipboardHistory>,
+    checkpoint: State<'_, HistoryCheckpoint>
+) -> Result<HistoryDelta, String> {
     let history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
-    Ok(history_guard.iter().cloned().collect())
+    let checkpoint_guard = checkpoint.lock().map_err(|e| format!("Failed to lock checkpoint: {}", e))?;
+    let empty = HashSet::new();
+    let saved_ids = checkpoint_guard.as_ref().unwrap_or(&empty);
+
+    let current_ids: HashSet<u64> = history_guard.iter().map(|item| item.id).collect();
+    let added = history_guard
+        .iter()
+        .filter(|item| !saved_ids.contains(&item.id))
+        .cloned()
+        .collect();
+    let mut removed: Vec<u64> = saved_ids.difference(&current_ids).copied().collect();
+    removed.sort_unstable();
+
+    Ok(HistoryDelta { added, removed })
 }
 
-// Tauri command to add item to clipboard history manually (for testing)
+// Tauri command to get which clipboard change-detection backend the monitor uses
 #[tauri::command]
-async fn add_to_history(
-    content: String,
-    history: State<'_, ClipboardHistory>
+async fn get_monitor_backend(backend: State<'_, MonitorBackend>) -> Result<Option<String>, String> {
+    let backend_guard = backend.lock().map_err(|e| format!("Failed to lock monitor backend: {}", e))?;
+    Ok(backend_guard.map(|name| name.to_string()))
+}
+
+// Tauri command to read the most recent audit log entries, oldest first
+#[tauri::command]
+async fn read_audit_log(app: AppHandle, limit: usize) -> Result<Vec<audit::AuditEntry>, String> {
+    audit::read_tail(&data_dir(&app)?.join(audit::FILE_NAME), limit)
+}
+
+// Tauri command to set the largest content size sent in `clipboard-updated` events
+#[tauri::command]
+async fn set_max_emit_payload(
+    app: AppHandle,
+    max_bytes: Option<usize>,
+    settings: State<'_, AppSettings>
 ) -> Result<(), String> {
-    add_item_to_history(&content, &history).await
+    {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.max_emit_payload_bytes = max_bytes;
+    }
+    persist_settings(&app);
+    Ok(())
 }
 
-// Helper function to add items to history (used by both manual and automatic monitoring)
-async fn add_item_to_history(content: &str, history: &ClipboardHistory) -> Result<(), String> {
-    if content.trim().is_empty() {
-        return Ok(()); // Don't add empty content
+// Tauri command to get the key content is deduplicated by under the current settings
+#[tauri::command]
+async fn get_dedup_key(content: String, settings: State<'_, AppSettings>) -> Result<String, String> {
+    let settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+    Ok(dedup_key(&content, &settings_guard))
+}
+
+// Tauri command to set (or clear) the URL captured items are POSTed to
+#[tauri::command]
+async fn set_capture_webhook(
+    app: AppHandle,
+    url: Option<String>,
+    settings: State<'_, AppSettings>
+) -> Result<(), String> {
+    if let Some(url) = &url {
+        webhook::validate_url(url)?;
+    }
+    {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.capture_webhook_url = url;
     }
+    persist_settings(&app);
+    Ok(())
+}
 
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    
-    let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
-    
-    // Check if this content is already the most recent item (avoid duplicates)
-    if let Some(latest) = history_guard.front() {
-        if latest.content == content {
-            return Ok(()); // Don't add duplicate
-        }
+// Tauri command to turn the localhost ingest server on or off
+#[tauri::command]
+async fn set_ingest_server_enabled(
+    app: AppHandle,
+    enabled: bool,
+    settings: State<'_, AppSettings>
+) -> Result<(), String> {
+    {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.enable_ingest_server = enabled;
     }
-    
-    // Generate a simple ID based on timestamp and length
-    let id = timestamp + history_guard.len() as u64;
-    
-    let item = ClipboardItem {
-        id,
-        content: content.to_string(),
-        timestamp,
-        content_type: "text".to_string(),
+    let result = sync_ingest_server(&app);
+    persist_settings(&app);
+    result
+}
+
+// Tauri command to set (or clear, with None) the idle time after which
+// monitoring pauses itself
+#[tauri::command]
+async fn set_idle_auto_pause(
+    app: AppHandle,
+    seconds: Option<u64>,
+    settings: State<'_, AppSettings>
+) -> Result<(), String> {
+    if seconds == Some(0) {
+        return Err("Idle auto-pause must be at least 1 second".to_string());
+    }
+    {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.auto_pause_when_idle_secs = seconds;
+    }
+    persist_settings(&app);
+    Ok(())
+}
+
+// Start or stop the ingest server to match the settings. A port that's
+// already taken is reported as an error and leaves the server off.
+fn sync_ingest_server(app: &AppHandle) -> Result<(), String> {
+    let (enabled, port, token) = {
+        let settings = app.state::<AppSettings>();
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        if settings_guard.enable_ingest_server && settings_guard.ingest_token.is_none() {
+            settings_guard.ingest_token = Some(ingest::generate_token());
+        }
+        (
+            settings_guard.enable_ingest_server,
+            settings_guard.ingest_port.unwrap_or(ingest::DEFAULT_PORT),
+            settings_guard.ingest_token.clone().unwrap_or_default(),
+        )
     };
-    
-    // Add to front of deque (newest first)
-    history_guard.push_front(item);
-    
-    // Keep only last 100 items
-    if history_guard.len() > 100 {
-        history_guard.pop_back();
+
+    let server = app.state::<IngestServer>();
+    let mut server_guard = server.lock().map_err(|e| format!("Failed to lock ingest server: {}", e))?;
+    // Leave a server that already has the wanted port and token alone
+    if enabled && server_guard.as_ref().is_some_and(|running| running.port == port && running.token == token) {
+        return Ok(());
+    }
+    // Otherwise restart, waiting for the old server to release the port first
+    if let Some(running) = server_guard.take() {
+        running.stop();
+    }
+    if enabled {
+        *server_guard = Some(ingest::start(app.clone(), port, token)?);
     }
-    
     Ok(())
 }
 
-// Tauri command to start clipboard monitoring
+// Tauri command to set (or clear, with None) the capture expression
 #[tauri::command]
-async fn start_clipboard_monitoring(
+async fn set_capture_expression(
     app: AppHandle,
-    history: State<'_, ClipboardHistory>,
-    ignore_flag: State<'_, IgnoreNextClipboard>
+    expression: Option<String>,
+    settings: State<'_, AppSettings>
 ) -> Result<(), String> {
-    let app_clone = app.clone();
-    let history_clone = history.inner().clone();
-    let ignore_flag_clone = ignore_flag.inner().clone();
-    
-    // Spawn background thread for clipboard monitoring
-    thread::spawn(move || {
-        let mut last_clipboard_content = String::new();
-        
-        loop {
-            // Check clipboard every 500ms
-            thread::sleep(Duration::from_millis(500));
-            
-            // Get current clipboard content
-            if let Ok(current_content) = app_clone.clipboard().read_text() {
-                // If content changed, check if we should ignore it
-                if current_content != last_clipboard_content && !current_content.trim().is_empty() {
-                    // Check if we should ignore this change
-                    let should_ignore = {
-                        if let Ok(mut ignore_guard) = ignore_flag_clone.lock() {
-                            let ignore = *ignore_guard;
-                            if ignore {
-                                *ignore_guard = false; // Reset flag after checking
-                                true
-                            } else {
-                                false
-                            }
-                        } else {
-                            false
-                        }
-                    };
-                    
-                    if should_ignore {
-                        last_clipboard_content = current_content;
-                        continue;
-                    }
-                    // Create a simple blocking version for the thread
-                    let timestamp = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
-                    
-                    if let Ok(mut history_guard) = history_clone.lock() {
-                        // Check if this content is already the most recent item
-                        if let Some(latest) = history_guard.front() {
-                            if latest.content == current_content {
-                                continue; // Skip duplicate
-                            }
-                        }
-                        
-                        let id = timestamp + history_guard.len() as u64;
-                        let item = ClipboardItem {
-                            id,
-                            content: current_content.clone(),
-                            timestamp,
-                            content_type: "text".to_string(),
-                        };
-                        
-                        history_guard.push_front(item);
-                        
-                        // Keep only last 100 items
-                        if history_guard.len() > 100 {
-                            history_guard.pop_back();
-                        }
-                        
-                        println!("Added clipboard item: {}", current_content.chars().take(50).collect::<String>());
-                        
-                        // Emit event to frontend to refresh history
-                        if let Err(e) = app_clone.emit("clipboard-updated", &current_content) {
-                            eprintln!("Failed to emit clipboard update event: {}", e);
-                        }
-                    }
-                    
-                    last_clipboard_content = current_content;
-                }
-            }
-        }
-    });
-    
+    if let Some(expression) = &expression {
+        capture_rule::validate(expression)?;
+    }
+    {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.capture_expression = expression;
+    }
+    refresh_capture_filters(&app)?;
+    persist_settings(&app);
     Ok(())
 }
 
-// Tauri command to copy text to clipboard without triggering monitoring
+// Tauri command to set preview lengths per content type and recompute the
+// stored previews of every item, in all workspaces
 #[tauri::command]
-async fn copy_to_clipboard(
+async fn set_preview_lengths(
     app: AppHandle,
-    content: String,
-    ignore_flag: State<'_, IgnoreNextClipboard>
+    lengths: HashMap<String, usize>,
+    settings: State<'_, AppSettings>,
+    workspaces: State<'_, Workspaces>,
+    history: State<'_, ClipboardHistory>
 ) -> Result<(), String> {
-    // Set flag to ignore the next clipboard change
     {
-        let mut ignore_guard = ignore_flag.lock().map_err(|e| format!("Failed to lock ignore flag: {}", e))?;
-        *ignore_guard = true;
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.preview_lengths = lengths;
+        let mut workspaces_guard = workspaces.lock().map_err(|e| format!("Failed to lock workspaces: {}", e))?;
+        let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
+        refresh_previews(workspaces_guard.inactive.values_mut().flatten(), &settings_guard);
+        refresh_previews(history_guard.iter_mut(), &settings_guard);
     }
-    
-    // Copy to clipboard
-    app.clipboard().write_text(content)
-        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
-    
+    persist_settings(&app);
+    persist_workspaces(&app);
+    persist_history(&app);
     Ok(())
 }
 
-// Tauri command to clear clipboard history
+// Tauri command to set the capture allowlist (an empty list captures everything
+// not blocklisted)
 #[tauri::command]
-async fn clear_clipboard_history(history: State<'_, ClipboardHistory>) -> Result<(), String> {
-    let mut history_guard = history.lock().map_err(|e| format!("Failed to lock history: {}", e))?;
-    history_guard.clear();
+async fn set_allowlist_patterns(
+    app: AppHandle,
+    patterns: Vec<String>,
+    settings: State<'_, AppSettings>
+) -> Result<(), String> {
+    compile_patterns(&patterns)?;
+    {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        settings_guard.allowlist_patterns = patterns;
+    }
+    refresh_capture_filters(&app)?;
+    persist_settings(&app);
+    Ok(())
+}
+
+// Tauri command to get the current settings
+#[tauri::command]
+async fn get_settings(settings: State<'_, AppSettings>) -> Result<Settings, String> {
+    let settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+    Ok(settings_guard.clone())
+}
+
+// Helper to reject settings whose patterns, webhook URL or capture expression
+// don't parse
+fn validate_settings(settings: &Settings) -> Result<(), String> {
+    compile_patterns(&settings.hash_only_patterns)?;
+    compile_patterns(&settings.allowlist_patterns)?;
+    compile_patterns(&settings.blocklist_patterns)?;
+    if let Some(url) = &settings.capture_webhook_url {
+        webhook::validate_url(url)?;
+    }
+    if let Some(expression) = &settings.capture_expression {
+        capture_rule::validate(expression)?;
+    }
+    Ok(())
+}
+
+// Helper to make settings read from disk usable. Anything validate_settings
+// rejects is dropped and logged, since one bad pattern would otherwise make
+// every capture fail.
+fn repair_settings(settings: &mut Settings) {
+    let Err(e) = validate_settings(settings) else {
+        return;
+    };
+    eprintln!("Saved settings are invalid, dropping what doesn't parse: {}", e);
+    for patterns in [
+        &mut settings.hash_only_patterns,
+        &mut settings.allowlist_patterns,
+        &mut settings.blocklist_patterns,
+    ] {
+        patterns.retain(|pattern| Regex::new(pattern).is_ok());
+    }
+    if settings.capture_webhook_url.as_deref().is_some_and(|url| webhook::validate_url(url).is_err()) {
+        settings.capture_webhook_url = None;
+    }
+    if settings.capture_expression.as_deref().is_some_and(|expression| capture_rule::validate(expression).is_err()) {
+        settings.capture_expression = None;
+    }
+}
+
+// Whether the ingest server has to be restarted to go from one settings to the other
+fn ingest_settings_changed(old: &Settings, new: &Settings) -> bool {
+    old.enable_ingest_server != new.enable_ingest_server
+        || old.ingest_port != new.ingest_port
+        || old.ingest_token != new.ingest_token
+}
+
+// Tauri command to replace the current settings
+#[tauri::command]
+async fn update_settings(
+    app: AppHandle,
+    new_settings: Settings,
+    settings: State<'_, AppSettings>
+) -> Result<(), String> {
+    validate_settings(&new_settings)?;
+    let ingest_changed = {
+        let mut settings_guard = settings.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+        let changed = ingest_settings_changed(&settings_guard, &new_settings);
+        *settings_guard = new_settings;
+        changed
+    };
+    refresh_capture_filters(&app)?;
+    let result = if ingest_changed { sync_ingest_server(&app) } else { Ok(()) };
+    persist_settings(&app);
+    result
+}
+
+// Tauri command to report whether history is actually being saved to disk.
+// Frontends should call this at startup rather than rely on `persistence-warning`.
+#[tauri::command]
+async fn get_persistence_status(persistence: State<'_, Persistence>) -> Result<PersistenceStatus, String> {
+    let status_guard = persistence.lock().map_err(|e| format!("Failed to lock persistence status: {}", e))?;
+    Ok(status_guard.clone())
+}
+
+// Tauri command to pause capturing (incognito mode)
+#[tauri::command]
+async fn pause_monitoring(app: AppHandle, paused: State<'_, CapturePaused>) -> Result<(), String> {
+    set_paused(&app, &paused, true)
+}
+
+// Tauri command to resume capturing
+#[tauri::command]
+async fn resume_monitoring(app: AppHandle, paused: State<'_, CapturePaused>) -> Result<(), String> {
+    set_paused(&app, &paused, false)
+}
+
+// Tauri command to pause capturing for a fixed time, then resume automatically.
+// Any later pause or resume cancels the automatic resume.
+#[tauri::command]
+async fn pause_for(
+    app: AppHandle,
+    duration_secs: u64,
+    paused: State<'_, CapturePaused>,
+    timer: State<'_, PauseTimer>
+) -> Result<(), String> {
+    if duration_secs == 0 {
+        return Err("Pause duration must be at least 1 second".to_string());
+    }
+    set_paused(&app, &paused, true)?;
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let mut timer_guard = timer.lock().map_err(|e| format!("Failed to lock pause timer: {}", e))?;
+        *timer_guard = Some(cancelled.clone());
+    }
+    let paused = paused.inner().clone();
+    let timer = timer.inner().clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(duration_secs));
+        // Resume with the timer locked, and only if it's still this thread's,
+        // so a pause that lands meanwhile can't be ended early
+        let Ok(mut timer_guard) = timer.lock() else {
+            return;
+        };
+        let still_ours = timer_guard
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, &cancelled) && !current.load(Ordering::Relaxed));
+        if !still_ours {
+            return;
+        }
+        timer_guard.take();
+        if let Err(e) = apply_paused(&app, &paused, false) {
+            eprintln!("Failed to resume capturing: {}", e);
+        }
+    });
+    Ok(())
+}
+
+// Tauri command to check whether capturing is manually paused
+#[tauri::command]
+async fn is_monitoring_paused(paused: State<'_, CapturePaused>) -> Result<bool, String> {
+    let paused_guard = paused.lock().map_err(|e| format!("Failed to lock pause flag: {}", e))?;
+    Ok(*paused_guard)
+}
+
+// Helper to flip the pause flag and let the frontend know. Cancels any
+// pending auto-resume, since the user has changed the state themselves.
+fn set_paused(app: &AppHandle, paused: &CapturePaused, value: bool) -> Result<(), String> {
+    if let Ok(mut timer_guard) = app.state::<PauseTimer>().lock() {
+        if let Some(cancelled) = timer_guard.take() {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+    apply_paused(app, paused, value)
+}
+
+// Helper to set the pause flag and emit the change, leaving the timer alone
+fn apply_paused(app: &AppHandle, paused: &CapturePaused, value: bool) -> Result<(), String> {
+    {
+        let mut paused_guard = paused.lock().map_err(|e| format!("Failed to lock pause flag: {}", e))?;
+        *paused_guard = value;
+    }
+    if let Err(e) = app.emit("capture-paused-changed", value) {
+        eprintln!("Failed to emit pause state event: {}", e);
+    }
     Ok(())
 }
 
@@ -208,22 +3704,160 @@ async fn clear_clipboard_history(history: State<'_, ClipboardHistory>) -> Result
 pub fn run() {
     // Initialize clipboard history state
     let clipboard_history: ClipboardHistory = Arc::new(Mutex::new(VecDeque::new()));
-    // Initialize ignore flag state
-    let ignore_next_clipboard: IgnoreNextClipboard = Arc::new(Mutex::new(false));
+    // Initialize self-write tracking state
+    let self_writes: SelfWrites = Arc::new(Mutex::new(SelfWriteTracker::default()));
+    // Initialize settings and pause state
+    let settings: AppSettings = Arc::new(Mutex::new(Settings::default()));
+    let capture_filters: CaptureFilters = Arc::new(Mutex::new(CompiledFilters::default()));
+    let hash_key: HashKey = Arc::new(Mutex::new(generate_hash_key()));
+    let capture_paused: CapturePaused = Arc::new(Mutex::new(false));
+    let pause_timer: PauseTimer = Arc::new(Mutex::new(None));
+    let read_latency: ReadLatency = Arc::new(Mutex::new(LatencyStats::default()));
+    let history_checkpoint: HistoryCheckpoint = Arc::new(Mutex::new(None));
+    let monitor_backend: MonitorBackend = Arc::new(Mutex::new(None));
+    let auto_tag_rules: AutoTagRules = Arc::new(Mutex::new(Vec::new()));
+    let append_mode: AppendMode = Arc::new(Mutex::new(None));
+    let pin_order: PinOrder = Arc::new(Mutex::new(Vec::new()));
+    let persistence: Persistence = Arc::new(Mutex::new(PersistenceStatus::default()));
+    let metrics: Metrics = Arc::new(Mutex::new(CaptureMetrics::default()));
+    let ingest_server: IngestServer = Arc::new(Mutex::new(None));
+    let last_copied: LastCopied = Arc::new(Mutex::new(None));
+    let redaction_rulesets: RedactionRulesets = Arc::new(Mutex::new(HashMap::new()));
+    let saved_searches: SavedSearches = Arc::new(Mutex::new(Vec::new()));
+    let quick_slots: QuickSlots = Arc::new(Mutex::new([None; 9]));
+    let workspaces: Workspaces = Arc::new(Mutex::new(WorkspaceStore::default()));
+    let history_writer: HistoryWriter = Arc::new(Mutex::new(PersistQueue::default()));
+    let history_index: HistoryIndex = Arc::new(Mutex::new(search_index::SearchIndex::default()));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(clipboard_history)
-        .manage(ignore_next_clipboard)
+        .manage(self_writes)
+        .manage(settings)
+        .manage(capture_filters)
+        .manage(hash_key)
+        .manage(capture_paused)
+        .manage(pause_timer)
+        .manage(read_latency)
+        .manage(history_checkpoint)
+        .manage(monitor_backend)
+        .manage(auto_tag_rules)
+        .manage(append_mode)
+        .manage(pin_order)
+        .manage(persistence)
+        .manage(metrics)
+        .manage(ingest_server)
+        .manage(last_copied)
+        .manage(history_writer)
+        .manage(workspaces)
+        .manage(quick_slots)
+        .manage(saved_searches)
+        .manage(redaction_rulesets)
+        .manage(history_index)
+        .setup(|app| {
+            init_persistence(app.handle());
+            if let Err(e) = sync_ingest_server(app.handle()) {
+                eprintln!("Ingest server not started: {}", e);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_clipboard_text,
             get_clipboard_history,
+            sample_history,
+            latest_per_source,
+            get_history_by_display,
             add_to_history,
             start_clipboard_monitoring,
             copy_to_clipboard,
-            clear_clipboard_history
+            copy_history_item,
+            copy_sequence,
+            promote_current,
+            get_slots,
+            assign_slot,
+            clear_slot,
+            copy_slot,
+            get_last_copied_id,
+            set_self_write_expiry,
+            get_pending_self_writes,
+            clear_clipboard_history,
+            get_settings,
+            update_settings,
+            pause_monitoring,
+            resume_monitoring,
+            pause_for,
+            is_monitoring_paused,
+            get_read_latency_ms,
+            checkpoint,
+            diff_since_checkpoint,
+            get_monitor_backend,
+            get_clipboard_change_count,
+            tag_item,
+            untag_item,
+            set_item_metadata,
+            get_item_metadata,
+            mark_secret,
+            scan_for_secrets,
+            add_auto_tag_rule,
+            list_auto_tag_rules,
+            remove_auto_tag_rule,
+            add_redaction_ruleset,
+            list_redaction_rulesets,
+            redact_item,
+            export_transformed,
+            read_audit_log,
+            enable_append_mode,
+            disable_append_mode,
+            get_history_item,
+            get_by_cid,
+            set_max_emit_payload,
+            pin_item,
+            unpin_item,
+            set_pin_order,
+            get_dedup_key,
+            get_persistence_status,
+            flush_now,
+            get_persist_stats,
+            get_storage_report,
+            keep_only,
+            list_workspaces,
+            switch_workspace,
+            export_backup,
+            restore_backup,
+            consolidate_across_workspaces,
+            import_lines,
+            merge_prefix_chains,
+            keep_top_per_tag,
+            get_capture_metrics,
+            set_capture_webhook,
+            set_ingest_server_enabled,
+            set_idle_auto_pause,
+            set_allowlist_patterns,
+            set_capture_expression,
+            search_history,
+            save_search,
+            list_saved_searches,
+            run_saved_search,
+            rebuild_search_index,
+            get_diversity_stats,
+            detect_sessions,
+            get_history_tree,
+            word_frequency,
+            content_category_histogram,
+            common_substring,
+            set_preview_lengths,
+            export_bundle,
+            import_bundle
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Don't lose debounced history changes on quit
+            if let tauri::RunEvent::Exit = event {
+                if let Err(e) = flush_history(app) {
+                    eprintln!("Failed to save history on exit: {}", e);
+                }
+            }
+        });
 }
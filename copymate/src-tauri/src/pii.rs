@@ -0,0 +1,125 @@
+// Detection of personally identifiable information (credit cards, SSNs,
+// emails) in captured content. Only the kind of match is ever reported,
+// never the matched value itself.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub const CREDIT_CARD: &str = "credit_card";
+pub const SSN: &str = "ssn";
+pub const EMAIL: &str = "email";
+
+// 13-19 digits, optionally grouped with spaces or dashes
+pub const CARD_PATTERN: &str = r"\b\d(?:[ -]?\d){12,18}\b";
+pub const SSN_PATTERN: &str = r"\b(\d{3})-(\d{2})-(\d{4})\b";
+pub const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+
+fn card_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(CARD_PATTERN).unwrap())
+}
+
+fn ssn_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(SSN_PATTERN).unwrap())
+}
+
+fn email_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(EMAIL_PATTERN).unwrap())
+}
+
+// Standard Luhn checksum over a string of ASCII digits
+pub fn luhn_valid(digits: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in digits.chars().rev().enumerate() {
+        let Some(mut digit) = c.to_digit(10) else {
+            return false;
+        };
+        if i % 2 == 1 {
+            digit *= 2;
+            if digit > 9 {
+                digit -= 9;
+            }
+        }
+        sum += digit;
+    }
+    !digits.is_empty() && sum % 10 == 0
+}
+
+// Whether the content contains a Luhn-valid card number
+pub fn contains_card_number(content: &str) -> bool {
+    card_regex().find_iter(content).any(|m| {
+        let digits: String = m.as_str().chars().filter(|c| c.is_ascii_digit()).collect();
+        luhn_valid(&digits)
+    })
+}
+
+// Whether the content contains something shaped like a valid SSN
+pub fn contains_ssn(content: &str) -> bool {
+    ssn_regex().captures_iter(content).any(|caps| {
+        let area = &caps[1];
+        // Area 000, 666 and 9xx, group 00 and serial 0000 are never issued
+        area != "000" && area != "666" && !area.starts_with('9') && &caps[2] != "00" && &caps[3] != "0000"
+    })
+}
+
+pub fn contains_email(content: &str) -> bool {
+    email_regex().is_match(content)
+}
+
+// Returns the kinds of PII found in the content
+pub fn detect(content: &str) -> Vec<&'static str> {
+    let mut kinds = Vec::new();
+    if contains_card_number(content) {
+        kinds.push(CREDIT_CARD);
+    }
+    if contains_ssn(content) {
+        kinds.push(SSN);
+    }
+    if contains_email(content) {
+        kinds.push(EMAIL);
+    }
+    kinds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn luhn_accepts_valid_numbers() {
+        assert!(luhn_valid("4111111111111111"));
+        assert!(luhn_valid("79927398713"));
+    }
+
+    #[test]
+    fn luhn_rejects_bad_checksums_and_non_digits() {
+        assert!(!luhn_valid("4111111111111112"));
+        assert!(!luhn_valid("79927398710"));
+        assert!(!luhn_valid(""));
+        assert!(!luhn_valid("4111-1111-1111-1111"));
+    }
+
+    #[test]
+    fn ssn_found_in_text() {
+        assert!(contains_ssn("SSN: 123-45-6789"));
+        assert!(contains_ssn("first 000-12-3456, then 234-56-7890"));
+    }
+
+    #[test]
+    fn ssn_ignores_numbers_never_issued() {
+        assert!(!contains_ssn("000-12-3456"));
+        assert!(!contains_ssn("666-12-3456"));
+        assert!(!contains_ssn("912-34-5678"));
+        assert!(!contains_ssn("123-00-4567"));
+        assert!(!contains_ssn("123-45-0000"));
+    }
+
+    #[test]
+    fn ssn_requires_dashed_groups() {
+        assert!(!contains_ssn("123456789"));
+        assert!(!contains_ssn("123 45 6789"));
+        assert!(!contains_ssn("1234-56-7890"));
+    }
+}
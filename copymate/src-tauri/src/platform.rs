@@ -0,0 +1,381 @@
+// Platform-specific helpers. Everything here is best-effort: when the
+// current OS doesn't expose the information we return `None` and callers
+// treat the related feature as a no-op.
+
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+use std::process::Command;
+
+// Process names that only run while a screen share or recording is active.
+// OBS is left out on purpose: it's often left open without recording, and
+// pausing capture whenever it runs would be a constant false positive.
+#[cfg(target_os = "macos")]
+const SCREENSHARE_PROCESSES: &[&str] = &["CptHost", "screencaptureui", "ScreenFlow"];
+
+#[cfg(target_os = "windows")]
+const SCREENSHARE_PROCESSES: &[&str] = &["CptHost.exe"];
+
+#[cfg(target_os = "linux")]
+const SCREENSHARE_PROCESSES: &[&str] = &["wf-recorder", "simplescreenrecorder", "gpu-screen-recorder"];
+
+// Keeps tasklist from flashing a console window, since the app is a GUI process
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+// List the names of running processes, one per line
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn running_process_names() -> Option<Vec<String>> {
+    let output = Command::new("ps").args(["-A", "-o", "comm="]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let names = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // macOS reports full executable paths, keep only the file name
+        .map(|line| line.trim().rsplit('/').next().unwrap_or("").to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    Some(names)
+}
+
+#[cfg(target_os = "windows")]
+fn running_process_names() -> Option<Vec<String>> {
+    use std::os::windows::process::CommandExt;
+
+    let output = Command::new("tasklist")
+        .args(["/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let names = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // Each row looks like "name.exe","1234",... so take the first quoted field
+        .filter_map(|line| line.split(',').next())
+        .map(|field| field.trim_matches('"').to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    Some(names)
+}
+
+// Returns whether a screen share / recording session looks active, or `None`
+// when this platform has no way to tell
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+pub fn is_screen_sharing() -> Option<bool> {
+    let names = running_process_names()?;
+    Some(names.iter().any(|name| {
+        SCREENSHARE_PROCESSES
+            .iter()
+            .any(|known| name.eq_ignore_ascii_case(known))
+    }))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn is_screen_sharing() -> Option<bool> {
+    None
+}
+
+// Returns the name of the application that currently has focus, or `None`
+// when it can't be determined on this platform
+pub fn frontmost_app() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::frontmost_app_name()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::frontmost_app_name()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+// Returns the pasteboard's change count (the clipboard sequence number on
+// Windows), which increases every time the clipboard contents change, or
+// `None` where no such counter is available
+pub fn clipboard_change_count() -> Option<u64> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::pasteboard_change_count()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::clipboard_sequence_number()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+// Index of the display the user is working on: the one holding the foreground
+// window on Windows, the one under the mouse pointer on macOS. `None` where
+// it can't be determined.
+pub fn active_display() -> Option<u32> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::pointer_display_index()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::foreground_display_index()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+// Seconds since the last keyboard/mouse input, or `None` where idle time
+// can't be detected
+pub fn idle_seconds() -> Option<u64> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::idle_seconds()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::idle_seconds()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{c_char, c_void, CStr};
+
+    type Id = *mut c_void;
+    type Sel = *mut c_void;
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> Id;
+        fn sel_registerName(name: *const c_char) -> Sel;
+        fn objc_msgSend();
+        fn objc_autoreleasePoolPush() -> *mut c_void;
+        fn objc_autoreleasePoolPop(pool: *mut c_void);
+    }
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {}
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+        fn CGEventCreate(source: *mut c_void) -> *mut c_void;
+        fn CGEventGetLocation(event: *mut c_void) -> CGPoint;
+        fn CGGetDisplaysWithPoint(point: CGPoint, max_displays: u32, displays: *mut u32, count: *mut u32) -> i32;
+        fn CGGetActiveDisplayList(max_displays: u32, displays: *mut u32, count: *mut u32) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(object: *const c_void);
+    }
+
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const ANY_INPUT_EVENT_TYPE: u32 = !0;
+    const MAX_DISPLAYS: usize = 16;
+
+    // Send a no-argument message that returns an object pointer
+    unsafe fn send(receiver: Id, selector: &CStr) -> Id {
+        if receiver.is_null() {
+            return std::ptr::null_mut();
+        }
+        let msg_send: unsafe extern "C" fn(Id, Sel) -> Id =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        msg_send(receiver, sel_registerName(selector.as_ptr()))
+    }
+
+    pub fn frontmost_app_name() -> Option<String> {
+        unsafe {
+            // We're called from the monitor thread, which has no autorelease pool
+            let pool = objc_autoreleasePoolPush();
+            let workspace = send(objc_getClass(c"NSWorkspace".as_ptr()), c"sharedWorkspace");
+            let app = send(workspace, c"frontmostApplication");
+            let name = send(app, c"localizedName");
+            let utf8 = send(name, c"UTF8String") as *const c_char;
+            let result = if utf8.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+            };
+            objc_autoreleasePoolPop(pool);
+            result
+        }
+    }
+
+    pub fn pointer_display_index() -> Option<u32> {
+        unsafe {
+            // An event with no source reports the current pointer location
+            let event = CGEventCreate(std::ptr::null_mut());
+            if event.is_null() {
+                return None;
+            }
+            let location = CGEventGetLocation(event);
+            CFRelease(event);
+
+            let (mut display, mut count) = (0u32, 0u32);
+            if CGGetDisplaysWithPoint(location, 1, &mut display, &mut count) != 0 || count == 0 {
+                return None;
+            }
+            let mut displays = [0u32; MAX_DISPLAYS];
+            let mut active = 0u32;
+            if CGGetActiveDisplayList(MAX_DISPLAYS as u32, displays.as_mut_ptr(), &mut active) != 0 {
+                return None;
+            }
+            displays[..active as usize]
+                .iter()
+                .position(|id| *id == display)
+                .and_then(|index| u32::try_from(index).ok())
+        }
+    }
+
+    pub fn idle_seconds() -> Option<u64> {
+        let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE) };
+        (seconds.is_finite() && seconds >= 0.0).then_some(seconds as u64)
+    }
+
+    pub fn pasteboard_change_count() -> Option<u64> {
+        unsafe {
+            let pasteboard = send(objc_getClass(c"NSPasteboard".as_ptr()), c"generalPasteboard");
+            if pasteboard.is_null() {
+                return None;
+            }
+            // changeCount returns an NSInteger rather than an object
+            let msg_send: unsafe extern "C" fn(Id, Sel) -> isize =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let count = msg_send(pasteboard, sel_registerName(c"changeCount".as_ptr()));
+            u64::try_from(count).ok()
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::ffi::c_void;
+
+    type Handle = *mut c_void;
+
+    type MonitorEnumProc = unsafe extern "system" fn(Handle, Handle, *mut c_void, isize) -> i32;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const MONITOR_DEFAULTTONULL: u32 = 0;
+
+    #[repr(C)]
+    struct LastInputInfo {
+        size: u32,
+        time: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> Handle;
+        fn GetWindowThreadProcessId(hwnd: Handle, process_id: *mut u32) -> u32;
+        fn GetLastInputInfo(info: *mut LastInputInfo) -> i32;
+        fn GetClipboardSequenceNumber() -> u32;
+        fn MonitorFromWindow(hwnd: Handle, flags: u32) -> Handle;
+        fn EnumDisplayMonitors(hdc: Handle, clip: *const c_void, callback: MonitorEnumProc, data: isize) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+        fn OpenProcess(access: u32, inherit_handle: i32, process_id: u32) -> Handle;
+        fn QueryFullProcessImageNameW(process: Handle, flags: u32, name: *mut u16, size: *mut u32) -> i32;
+        fn CloseHandle(handle: Handle) -> i32;
+    }
+
+    unsafe extern "system" fn collect_monitor(monitor: Handle, _hdc: Handle, _rect: *mut c_void, data: isize) -> i32 {
+        let monitors = &mut *(data as *mut Vec<Handle>);
+        monitors.push(monitor);
+        1
+    }
+
+    pub fn foreground_display_index() -> Option<u32> {
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_null() {
+                return None;
+            }
+            let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL);
+            if monitor.is_null() {
+                return None;
+            }
+            // Monitors are numbered in enumeration order
+            let mut monitors: Vec<Handle> = Vec::new();
+            let data = &mut monitors as *mut Vec<Handle> as isize;
+            if EnumDisplayMonitors(std::ptr::null_mut(), std::ptr::null(), collect_monitor, data) == 0 {
+                return None;
+            }
+            monitors
+                .iter()
+                .position(|handle| *handle == monitor)
+                .and_then(|index| u32::try_from(index).ok())
+        }
+    }
+
+    pub fn clipboard_sequence_number() -> Option<u64> {
+        // Zero means the sequence number isn't available to this process
+        match unsafe { GetClipboardSequenceNumber() } {
+            0 => None,
+            number => Some(u64::from(number)),
+        }
+    }
+
+    pub fn idle_seconds() -> Option<u64> {
+        let mut info = LastInputInfo {
+            size: std::mem::size_of::<LastInputInfo>() as u32,
+            time: 0,
+        };
+        unsafe {
+            if GetLastInputInfo(&mut info) == 0 {
+                return None;
+            }
+            // Tick counts wrap every ~49 days
+            Some(u64::from(GetTickCount().wrapping_sub(info.time)) / 1000)
+        }
+    }
+
+    pub fn frontmost_app_name() -> Option<String> {
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_null() {
+                return None;
+            }
+            let mut process_id = 0u32;
+            GetWindowThreadProcessId(hwnd, &mut process_id);
+            if process_id == 0 {
+                return None;
+            }
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+            if process.is_null() {
+                return None;
+            }
+            let mut buffer = [0u16; 260];
+            let mut size = buffer.len() as u32;
+            let ok = QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut size);
+            CloseHandle(process);
+            if ok == 0 {
+                return None;
+            }
+            // Turn "C:\\...\\Code.exe" into "Code"
+            let path = String::from_utf16_lossy(&buffer[..size as usize]);
+            let file_name = path.rsplit('\\').next().unwrap_or(&path);
+            Some(file_name.trim_end_matches(".exe").to_string())
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// Named sets of regex redactions for sharing snippets. They're applied to a
+// copy of the content; stored items are never changed.
+
+use regex::Regex;
+
+use crate::pii;
+
+// Text every match is replaced with
+pub const MASK: &str = "[REDACTED]";
+
+const IPV4_PATTERN: &str = r"\b(?:\d{1,3}\.){3}\d{1,3}\b";
+// Full eight-group form, groups before a "::" (fe80::1, 2001:db8::), or a
+// leading "::" (::1)
+const IPV6_PATTERN: &str = concat!(
+    r"\b(?:[0-9A-Fa-f]{1,4}:){7}[0-9A-Fa-f]{1,4}\b",
+    r"|\b[0-9A-Fa-f]{1,4}(?::[0-9A-Fa-f]{1,4}){0,6}::(?:[0-9A-Fa-f]{1,4}(?::[0-9A-Fa-f]{1,4}){0,6}\b)?",
+    r"|::[0-9A-Fa-f]{1,4}(?::[0-9A-Fa-f]{1,4}){0,6}\b",
+);
+// Common API key prefixes (Stripe, GitHub, Slack), JWTs and bearer tokens
+pub const API_KEY_PATTERN: &str = r"\b(?:sk|pk|rk|ghp|gho|ghs|xox[abpr])[-_][A-Za-z0-9_-]{10,}";
+pub const JWT_PATTERN: &str = r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+";
+pub const BEARER_PATTERN: &str = r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]+=*";
+
+// Names of the rulesets that ship with the app
+pub const BUILTIN_NAMES: [&str; 4] = ["emails", "ips", "tokens", "all-pii"];
+
+// Patterns of a built-in ruleset
+pub fn builtin_patterns(name: &str) -> Option<Vec<&'static str>> {
+    match name {
+        "emails" => Some(vec![pii::EMAIL_PATTERN]),
+        "ips" => Some(vec![IPV4_PATTERN, IPV6_PATTERN]),
+        "tokens" => Some(vec![API_KEY_PATTERN, JWT_PATTERN, BEARER_PATTERN]),
+        "all-pii" => Some(vec![pii::EMAIL_PATTERN, pii::SSN_PATTERN, pii::CARD_PATTERN, IPV4_PATTERN, IPV6_PATTERN]),
+        _ => None,
+    }
+}
+
+// Replace every match of every regex with the mask, in order
+pub fn apply(content: &str, regexes: &[Regex]) -> String {
+    regexes.iter().fold(content.to_string(), |text, regex| regex.replace_all(&text, MASK).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact_ips(content: &str) -> String {
+        let regexes: Vec<Regex> = builtin_patterns("ips")
+            .unwrap()
+            .into_iter()
+            .map(|pattern| Regex::new(pattern).unwrap())
+            .collect();
+        apply(content, &regexes)
+    }
+
+    #[test]
+    fn ipv6_full_form_redacted() {
+        assert_eq!(redact_ips("2001:0db8:85a3:0000:0000:8a2e:0370:7334"), MASK);
+    }
+
+    #[test]
+    fn ipv6_compressed_forms_redacted() {
+        assert_eq!(redact_ips("ping ::1 now"), "ping [REDACTED] now");
+        assert_eq!(redact_ips("link fe80::1 up"), "link [REDACTED] up");
+        assert_eq!(redact_ips("host 2001:db8::42."), "host [REDACTED].");
+        assert_eq!(redact_ips("fe80::1:2:3"), MASK);
+        assert_eq!(redact_ips("prefix 2001:db8::"), "prefix [REDACTED]");
+    }
+
+    #[test]
+    fn times_are_not_ipv6() {
+        assert_eq!(redact_ips("at 12:30:45"), "at 12:30:45");
+    }
+
+    #[test]
+    fn ipv4_redacted() {
+        assert_eq!(redact_ips("gateway 10.0.0.1"), "gateway [REDACTED]");
+    }
+}
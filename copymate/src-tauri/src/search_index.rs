@@ -0,0 +1,144 @@
+// Trigram inverted index over item content, used to narrow down candidates
+// before the exact substring match in `search_history`. Matching is
+// case-insensitive, so everything is indexed lowercased.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ClipboardItem;
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    // Trigram -> ids of items containing it
+    postings: HashMap<String, HashSet<u64>>,
+    // Id -> trigrams indexed for it, so removal doesn't need the content
+    docs: HashMap<u64, HashSet<String>>,
+}
+
+impl SearchIndex {
+    // Index (or re-index) an item's content
+    pub fn insert(&mut self, id: u64, content: &str) {
+        self.remove(id);
+        let grams = trigrams(content);
+        for gram in &grams {
+            self.postings.entry(gram.clone()).or_default().insert(id);
+        }
+        self.docs.insert(id, grams);
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        let Some(grams) = self.docs.remove(&id) else {
+            return;
+        };
+        for gram in grams {
+            if let Some(ids) = self.postings.get_mut(&gram) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.postings.remove(&gram);
+                }
+            }
+        }
+    }
+
+    pub fn rebuild<'a>(&mut self, items: impl IntoIterator<Item = &'a ClipboardItem>) {
+        self.postings.clear();
+        self.docs.clear();
+        for item in items {
+            self.insert(item.id, &item.content);
+        }
+    }
+
+    // Ids that could contain the query, or None if the query is too short for
+    // the index to help and every item has to be checked
+    pub fn candidates(&self, query: &str) -> Option<HashSet<u64>> {
+        let grams = trigrams(query);
+        if grams.is_empty() {
+            return None;
+        }
+        // Intersect starting from the rarest trigram to keep sets small
+        let mut postings: Vec<&HashSet<u64>> = Vec::with_capacity(grams.len());
+        for gram in &grams {
+            match self.postings.get(gram) {
+                Some(ids) => postings.push(ids),
+                None => return Some(HashSet::new()),
+            }
+        }
+        postings.sort_by_key(|ids| ids.len());
+        let mut result = postings[0].clone();
+        for ids in &postings[1..] {
+            result.retain(|id| ids.contains(id));
+        }
+        Some(result)
+    }
+}
+
+// Lowercased character trigrams of the text
+pub fn trigrams(text: &str) -> HashSet<String> {
+    let chars: Vec<char> = text.to_lowercase().chars().collect();
+    chars.windows(3).map(|window| window.iter().collect()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(values: &[u64]) -> HashSet<u64> {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn trigrams_are_lowercased_windows() {
+        assert_eq!(trigrams("AbCd"), ["abc", "bcd"].iter().map(|s| s.to_string()).collect());
+        assert!(trigrams("ab").is_empty());
+    }
+
+    #[test]
+    fn short_query_needs_full_scan() {
+        let mut index = SearchIndex::default();
+        index.insert(1, "hello world");
+        assert_eq!(index.candidates("he"), None);
+    }
+
+    #[test]
+    fn candidates_contain_every_trigram() {
+        let mut index = SearchIndex::default();
+        index.insert(1, "hello world");
+        index.insert(2, "help wanted");
+        index.insert(3, "goodbye");
+        assert_eq!(index.candidates("hel"), Some(ids(&[1, 2])));
+        assert_eq!(index.candidates("HELLO"), Some(ids(&[1])));
+        assert_eq!(index.candidates("xyz"), Some(ids(&[])));
+    }
+
+    #[test]
+    fn candidates_can_include_false_positives() {
+        // Both trigrams appear, just not next to each other
+        let mut index = SearchIndex::default();
+        index.insert(1, "abc xbcd");
+        assert_eq!(index.candidates("abcd"), Some(ids(&[1])));
+    }
+
+    #[test]
+    fn reinsert_replaces_old_content() {
+        let mut index = SearchIndex::default();
+        index.insert(1, "first draft");
+        index.insert(1, "final copy");
+        assert_eq!(index.candidates("draft"), Some(ids(&[])));
+        assert_eq!(index.candidates("copy"), Some(ids(&[1])));
+    }
+
+    #[test]
+    fn remove_drops_item_and_empty_postings() {
+        let mut index = SearchIndex::default();
+        index.insert(1, "shared text");
+        index.insert(2, "shared note");
+        index.remove(1);
+        assert_eq!(index.candidates("shared"), Some(ids(&[2])));
+        assert_eq!(index.candidates("text"), Some(ids(&[])));
+        assert!(!index.postings.contains_key("tex"));
+        assert!(!index.docs.contains_key(&1));
+
+        // Removing an unknown id is a no-op
+        index.remove(42);
+        assert_eq!(index.candidates("note"), Some(ids(&[2])));
+    }
+}
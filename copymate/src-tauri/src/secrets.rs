@@ -0,0 +1,66 @@
+// Heuristics for content that looks like a secret: API keys and tokens,
+// private keys, password assignments, card numbers and SSNs, and long
+// random-looking strings. Used to audit history after the fact.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::{pii, redact};
+
+// Tokens at least this long are checked for randomness
+const MIN_RANDOM_TOKEN_LEN: usize = 20;
+// Shannon entropy in bits per character above which a token looks random
+const RANDOM_TOKEN_ENTROPY: f64 = 4.0;
+
+fn secret_regexes() -> &'static [Regex] {
+    static RES: OnceLock<Vec<Regex>> = OnceLock::new();
+    RES.get_or_init(|| {
+        [
+            redact::API_KEY_PATTERN,
+            redact::JWT_PATTERN,
+            redact::BEARER_PATTERN,
+            r"-----BEGIN [A-Z ]*PRIVATE KEY-----",
+            r"\bAKIA[0-9A-Z]{16}\b",
+            r"(?i)\b(?:password|passwd|pwd|secret|api[_-]?key)\s*[:=]\s*\S+",
+        ]
+        .iter()
+        .map(|pattern| Regex::new(pattern).unwrap())
+        .collect()
+    })
+}
+
+// Whether the content is likely to contain a secret
+pub fn looks_secret(content: &str) -> bool {
+    secret_regexes().iter().any(|re| re.is_match(content))
+        || pii::contains_card_number(content)
+        || pii::contains_ssn(content)
+        || content.split_whitespace().any(looks_random)
+}
+
+// Long base64-style token mixing upper case, lower case and digits with
+// high per-character entropy. URLs and paths are ruled out by their characters.
+fn looks_random(token: &str) -> bool {
+    let len = token.chars().count();
+    if len < MIN_RANDOM_TOKEN_LEN
+        || token.starts_with('/')
+        || !token.chars().all(|c| c.is_ascii_alphanumeric() || "+/=_-".contains(c))
+        || !token.chars().any(|c| c.is_ascii_digit())
+        || !token.chars().any(|c| c.is_ascii_uppercase())
+        || !token.chars().any(|c| c.is_ascii_lowercase())
+    {
+        return false;
+    }
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in token.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let entropy: f64 = counts
+        .values()
+        .map(|count| {
+            let p = *count as f64 / len as f64;
+            -p * p.log2()
+        })
+        .sum();
+    entropy > RANDOM_TOKEN_ENTROPY
+}
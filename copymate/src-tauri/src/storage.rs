@@ -0,0 +1,46 @@
+// JSON files in the app data directory. Writes go through a temporary file
+// and a rename so a crash mid-write never leaves a truncated file behind.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+pub const HISTORY_FILE: &str = "history.json";
+pub const SETTINGS_FILE: &str = "settings.json";
+pub const WORKSPACES_FILE: &str = "workspaces.json";
+pub const SLOTS_FILE: &str = "slots.json";
+pub const SEARCHES_FILE: &str = "searches.json";
+pub const PIN_ORDER_FILE: &str = "pin_order.json";
+pub const AUTO_TAG_RULES_FILE: &str = "auto_tag_rules.json";
+pub const REDACTION_RULESETS_FILE: &str = "redaction_rulesets.json";
+pub const HASH_KEY_FILE: &str = "hash_key.json";
+
+// Name of the scratch file used to test that the directory is writable
+const PROBE_FILE: &str = ".write-test";
+
+pub fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+// Read a JSON file, returning None if it doesn't exist yet
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+// Check the directory accepts writes by creating and removing a probe file
+pub fn check_writable(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(PROBE_FILE);
+    fs::write(&probe, b"ok").map_err(|e| format!("Data directory {} is not writable: {}", dir.display(), e))?;
+    fs::remove_file(&probe).map_err(|e| format!("Failed to clean up {}: {}", probe.display(), e))
+}
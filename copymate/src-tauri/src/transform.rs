@@ -0,0 +1,47 @@
+// Text transforms applied to copies of items on export. Stored content is
+// never changed by these.
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    // Strip leading and trailing whitespace
+    Trim,
+    // Collapse every run of whitespace into a single space
+    CollapseWhitespace,
+    // Drop lines that are empty or only whitespace
+    RemoveBlankLines,
+    Lowercase,
+    Uppercase,
+}
+
+impl Transform {
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Transform::Trim => text.trim().to_string(),
+            Transform::CollapseWhitespace => text.split_whitespace().collect::<Vec<_>>().join(" "),
+            Transform::RemoveBlankLines => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Transform::Lowercase => text.to_lowercase(),
+            Transform::Uppercase => text.to_uppercase(),
+        }
+    }
+}
+
+// Apply each transform in turn
+pub fn apply_all(text: &str, transforms: &[Transform]) -> String {
+    transforms.iter().fold(text.to_string(), |text, transform| transform.apply(&text))
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    // Array of full items
+    Json,
+    // Item contents only, separated by blank lines
+    Text,
+}
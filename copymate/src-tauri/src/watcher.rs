@@ -0,0 +1,278 @@
+// Clipboard change detection for the monitor. Platforms with a change
+// notification or a cheap change counter use it; everything else falls back
+// to plain polling, where every tick is treated as a possible change.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::platform;
+
+pub trait ClipboardWatcher: Send {
+    // Block for at most `timeout`, returning true if the clipboard may have
+    // changed. A false return just means it's time for periodic housekeeping.
+    fn wait_for_change(&mut self, timeout: Duration) -> bool;
+
+    // Whether a reported change is a real clipboard write, so copying the
+    // same text again counts. Polling can't tell and reports every tick.
+    fn reports_real_changes(&self) -> bool {
+        true
+    }
+
+    // Short name of the backend, for diagnostics
+    fn name(&self) -> &'static str;
+}
+
+// Pick the best watcher available on this platform
+pub fn create() -> Box<dyn ClipboardWatcher> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(watcher) = windows::ListenerWatcher::start() {
+            return Box::new(watcher);
+        }
+    }
+    if let Some(watcher) = ChangeCountWatcher::new() {
+        return Box::new(watcher);
+    }
+    Box::new(PollingWatcher)
+}
+
+// Fallback that reports a possible change on every tick
+pub struct PollingWatcher;
+
+impl ClipboardWatcher for PollingWatcher {
+    fn wait_for_change(&mut self, timeout: Duration) -> bool {
+        thread::sleep(timeout);
+        true
+    }
+
+    fn reports_real_changes(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> &'static str {
+        "polling"
+    }
+}
+
+// Polls the platform change counter (macOS NSPasteboard.changeCount), which
+// is far cheaper than reading and comparing the clipboard text
+pub struct ChangeCountWatcher {
+    last_count: u64,
+}
+
+impl ChangeCountWatcher {
+    // How often the counter is sampled while waiting
+    const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+    fn new() -> Option<Self> {
+        // Start one behind so the current contents are read once on startup
+        let count = platform::clipboard_change_count()?;
+        Some(ChangeCountWatcher { last_count: count.wrapping_sub(1) })
+    }
+}
+
+impl ClipboardWatcher for ChangeCountWatcher {
+    fn wait_for_change(&mut self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match platform::clipboard_change_count() {
+                Some(count) if count != self.last_count => {
+                    self.last_count = count;
+                    return true;
+                }
+                Some(_) => {}
+                // Counter went away, behave like polling
+                None => return true,
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            thread::sleep(Self::SAMPLE_INTERVAL.min(deadline - now));
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "change-count"
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    // Event-driven watcher built on AddClipboardFormatListener. A hidden
+    // message-only window on its own thread receives WM_CLIPBOARDUPDATE and
+    // forwards it over a channel.
+
+    use super::ClipboardWatcher;
+    use std::ffi::c_void;
+    use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+    use std::sync::{Mutex, OnceLock};
+    use std::thread;
+    use std::time::Duration;
+
+    type Hwnd = *mut c_void;
+    type WndProc = unsafe extern "system" fn(Hwnd, u32, usize, isize) -> isize;
+
+    const HWND_MESSAGE: isize = -3;
+    const WM_CLIPBOARDUPDATE: u32 = 0x031D;
+
+    #[repr(C)]
+    struct WndClassW {
+        style: u32,
+        wnd_proc: WndProc,
+        cls_extra: i32,
+        wnd_extra: i32,
+        instance: *mut c_void,
+        icon: *mut c_void,
+        cursor: *mut c_void,
+        background: *mut c_void,
+        menu_name: *const u16,
+        class_name: *const u16,
+    }
+
+    #[repr(C)]
+    struct Msg {
+        hwnd: Hwnd,
+        message: u32,
+        wparam: usize,
+        lparam: isize,
+        time: u32,
+        pt_x: i32,
+        pt_y: i32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn RegisterClassW(class: *const WndClassW) -> u16;
+        fn CreateWindowExW(
+            ex_style: u32,
+            class_name: *const u16,
+            window_name: *const u16,
+            style: u32,
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            parent: Hwnd,
+            menu: *mut c_void,
+            instance: *mut c_void,
+            param: *mut c_void,
+        ) -> Hwnd;
+        fn DefWindowProcW(hwnd: Hwnd, message: u32, wparam: usize, lparam: isize) -> isize;
+        fn AddClipboardFormatListener(hwnd: Hwnd) -> i32;
+        fn GetMessageW(msg: *mut Msg, hwnd: Hwnd, filter_min: u32, filter_max: u32) -> i32;
+        fn DispatchMessageW(msg: *const Msg) -> isize;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleW(module_name: *const u16) -> *mut c_void;
+    }
+
+    // The window procedure can't capture state, so the sender lives here.
+    // Only one listener can exist per process.
+    static CHANGE_SENDER: OnceLock<Mutex<Sender<()>>> = OnceLock::new();
+
+    unsafe extern "system" fn window_proc(hwnd: Hwnd, message: u32, wparam: usize, lparam: isize) -> isize {
+        if message == WM_CLIPBOARDUPDATE {
+            if let Some(sender) = CHANGE_SENDER.get() {
+                if let Ok(sender) = sender.lock() {
+                    let _ = sender.send(());
+                }
+            }
+            return 0;
+        }
+        DefWindowProcW(hwnd, message, wparam, lparam)
+    }
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    pub struct ListenerWatcher {
+        changes: Receiver<()>,
+    }
+
+    impl ListenerWatcher {
+        pub fn start() -> Option<Self> {
+            let (change_tx, change_rx) = mpsc::channel();
+            CHANGE_SENDER.set(Mutex::new(change_tx)).ok()?;
+
+            // The window must be created on the thread that pumps its messages,
+            // so report back whether setup worked before entering the loop
+            let (ready_tx, ready_rx) = mpsc::channel();
+            thread::spawn(move || unsafe {
+                let class_name = wide("CopyMateClipboardListener");
+                let instance = GetModuleHandleW(std::ptr::null());
+                let class = WndClassW {
+                    style: 0,
+                    wnd_proc: window_proc,
+                    cls_extra: 0,
+                    wnd_extra: 0,
+                    instance,
+                    icon: std::ptr::null_mut(),
+                    cursor: std::ptr::null_mut(),
+                    background: std::ptr::null_mut(),
+                    menu_name: std::ptr::null(),
+                    class_name: class_name.as_ptr(),
+                };
+                if RegisterClassW(&class) == 0 {
+                    let _ = ready_tx.send(false);
+                    return;
+                }
+                let hwnd = CreateWindowExW(
+                    0,
+                    class_name.as_ptr(),
+                    class_name.as_ptr(),
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    HWND_MESSAGE as Hwnd,
+                    std::ptr::null_mut(),
+                    instance,
+                    std::ptr::null_mut(),
+                );
+                if hwnd.is_null() || AddClipboardFormatListener(hwnd) == 0 {
+                    let _ = ready_tx.send(false);
+                    return;
+                }
+                let _ = ready_tx.send(true);
+
+                let mut msg: Msg = std::mem::zeroed();
+                while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                    DispatchMessageW(&msg);
+                }
+            });
+
+            if ready_rx.recv().unwrap_or(false) {
+                Some(ListenerWatcher { changes: change_rx })
+            } else {
+                None
+            }
+        }
+    }
+
+    impl ClipboardWatcher for ListenerWatcher {
+        fn wait_for_change(&mut self, timeout: Duration) -> bool {
+            match self.changes.recv_timeout(timeout) {
+                Ok(()) => {
+                    // Collapse a burst of notifications into one read
+                    while self.changes.try_recv().is_ok() {}
+                    true
+                }
+                Err(RecvTimeoutError::Timeout) => false,
+                // Listener thread is gone, behave like polling
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(timeout);
+                    true
+                }
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "clipboard-listener"
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Post-capture webhook. Each delivery runs on its own thread with a request
+// timeout and a bounded number of retries, so a slow or dead endpoint never
+// holds up the monitor.
+
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+
+use crate::ClipboardItem;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_ATTEMPTS: u32 = 3;
+// Delay before the first retry, doubled after each failure
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub id: u64,
+    pub timestamp: u64,
+    pub content_type: String,
+    pub source_app: Option<String>,
+    pub content_hash: String,
+    pub content_length: usize,
+    pub tags: Vec<String>,
+    // Left out unless the user opts in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+impl WebhookPayload {
+    pub fn new(item: &ClipboardItem, include_content: bool) -> Self {
+        WebhookPayload {
+            id: item.id,
+            timestamp: item.timestamp,
+            content_type: item.content_type.clone(),
+            source_app: item.source_app.clone(),
+            content_hash: item.content_hash.clone(),
+            content_length: item.content_length,
+            tags: item.tags.clone(),
+            content: if include_content && !item.hash_only {
+                Some(item.content.clone())
+            } else {
+                None
+            },
+        }
+    }
+}
+
+pub fn validate_url(url: &str) -> Result<(), String> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(format!("Webhook URL must start with http:// or https://: {}", url))
+    }
+}
+
+// POST the payload in the background, retrying failed attempts
+pub fn deliver(url: String, payload: WebhookPayload) {
+    thread::spawn(move || {
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                eprintln!("Failed to serialize webhook payload: {}", e);
+                return;
+            }
+        };
+        let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            match agent
+                .post(&url)
+                .set("Content-Type", "application/json")
+                .send_string(&body)
+            {
+                Ok(_) => return,
+                Err(e) => eprintln!("Webhook attempt {}/{} to {} failed: {}", attempt, MAX_ATTEMPTS, url, e),
+            }
+            if attempt < MAX_ATTEMPTS {
+                thread::sleep(backoff);
+                backoff *= 2;
+            }
+        }
+    });
+}
@@ -25,10 +25,10 @@ function App() {
     }
   };
 
-  // Function to copy text to clipboard and highlight selection
-  const copyToClipboard = async (content: string, id: number) => {
+  // Function to copy a history item to clipboard and highlight selection
+  const copyToClipboard = async (id: number) => {
     try {
-      await invoke("copy_to_clipboard", { content });
+      await invoke("copy_history_item", { id });
       setSelectedId(id);
       // Clear selection after a brief moment
       setTimeout(() => setSelectedId(null), 200);
@@ -64,17 +64,29 @@ function App() {
     loadClipboardHistory();
     
     // Set up event listener for automatic clipboard updates
-    const unlisten = listen<string>("clipboard-updated", (event) => {
+    const unlisten = listen<ClipboardItem & { content_omitted: boolean }>("clipboard-updated", (event) => {
       console.log("Clipboard updated:", event.payload);
       loadClipboardHistory(); // Refresh history when clipboard changes
     });
 
+    // Warn when history can't be saved to disk. The status is also checked
+    // directly, since the warning event may have fired before we listened.
+    const unlistenPersistence = listen<string>("persistence-warning", (event) => {
+      console.warn("History will not be saved:", event.payload);
+    });
+    invoke<{ error: string | null }>("get_persistence_status")
+      .then((status) => {
+        if (status.error) console.warn("History will not be saved:", status.error);
+      })
+      .catch((error) => console.error("Failed to get persistence status:", error));
+
     // Start monitoring automatically
     startMonitoring();
 
     // Cleanup listener on unmount
     return () => {
       unlisten.then(fn => fn());
+      unlistenPersistence.then(fn => fn());
     };
   }, []);
 
@@ -97,7 +109,7 @@ function App() {
               <div
                 key={item.id}
                 className={`clipboard-item ${selectedId === item.id ? 'selected' : ''}`}
-                onClick={() => copyToClipboard(item.content, item.id)}
+                onClick={() => copyToClipboard(item.id)}
                 title={item.content} // Show full content on hover
               >
                 <div className="item-content">